@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: Vec<u32>| {
+    let _ = deck_codes::deck::Deck::new(&bytes);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|code: &str| {
+    if let Ok(deck) = deck_codes::decode_deck_code(code) {
+        // Anything that decodes must encode and decode back to the same deck
+        let reencoded = deck_codes::encode_deck_code(&deck);
+        assert_eq!(deck_codes::decode_deck_code(&reencoded), Ok(deck));
+    }
+});
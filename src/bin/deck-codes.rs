@@ -0,0 +1,473 @@
+//! The `deck-codes` command line tool. Install it with `cargo install deck_codes --features cli`.
+
+#![warn(clippy::all, clippy::pedantic)]
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use serde_json::{json, Value};
+
+use deck_codes::cards::CardDb;
+use deck_codes::constants::Class;
+use deck_codes::deck::Deck;
+use deck_codes::diff::{CardChange, DeckDiff};
+use deck_codes::error::DeckCodeError;
+use deck_codes::export::CardResolver;
+use deck_codes::rotation::Date;
+use deck_codes::serialization::DeckDefinition;
+use deck_codes::validation::Ruleset;
+use deck_codes::{decode_deck_code, decode_deck_code_lenient, encode_deck_code};
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0   Success
+  1   validate found rule violations, or diff found differences
+  2   Invalid arguments
+  3   An input file or the card data could not be read or parsed
+  10  InvalidBase64            20  MissingDeckCode
+  11  MissingHeaderByte        21  UnknownDeckFormat
+  12  TruncatedSection         22  UnknownVersion
+  13  InvalidVarint            23  ZeroCardCount
+  14  CountOverflow            24  CardCountOverflow
+  15  InvalidSideboardMarker   25  SideboardOwnerNotInDeck
+  16  SectionTooLarge          26  CardNotInDeck
+  17  InputTooLong             27  NotEnoughCopies
+  18  TrailingData             28  InvalidPatch
+  19  UnknownCard              29  Unknown
+  30  InvalidDeckEncoding (deprecated, no longer returned)";
+
+/// Decode, encode, validate and compare Hearthstone deck codes.
+#[derive(Parser)]
+#[command(name = "deck-codes", version, after_help = EXIT_CODES)]
+struct Cli {
+    /// Print JSON instead of text, including errors.
+    #[arg(long, global = true)]
+    json: bool,
+    /// A cards.json file from hearthstonejson.com, used to show card names and to check deck building rules.
+    #[arg(long, global = true, value_name = "FILE")]
+    cards: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the format, heroes and cards of a deck code.
+    Decode {
+        /// The deck code. Read from stdin when left out or `-`.
+        code: Option<String>,
+    },
+    /// Encode a JSON or TOML deck definition as a deck code.
+    Encode {
+        /// The definition file. Read from stdin when left out or `-`.
+        file: Option<PathBuf>,
+        /// The format of the definition. Defaults to TOML for `.toml` files and JSON otherwise.
+        #[arg(long, value_enum)]
+        input: Option<InputFormat>,
+    },
+    /// Check that a deck code is valid and, with --cards, that the deck follows the deck building rules.
+    Validate {
+        /// The deck code. Read from stdin when left out or `-`.
+        code: Option<String>,
+        /// Allow the sets legal in the deck's format on this date, written as YYYY-MM-DD. Needs --cards.
+        /// Defaults to the latest rotation in the rotation tables.
+        #[arg(long, value_parser = parse_date)]
+        date: Option<Date>,
+    },
+    /// List the cards that changed between two deck codes.
+    Diff { a: String, b: String },
+    /// Print the canonical form of a pasted deck code, fixing whitespace, padding and the URL-safe alphabet.
+    Canonicalize {
+        /// The deck code. Read from stdin when left out or `-`.
+        code: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputFormat {
+    Json,
+    Toml,
+}
+
+/// Why a command failed.
+enum Failure {
+    Deck(DeckCodeError),
+    Input(String),
+}
+
+impl From<DeckCodeError> for Failure {
+    fn from(error: DeckCodeError) -> Self {
+        Self::Deck(error)
+    }
+}
+
+impl Failure {
+    fn exit_code(&self) -> u8 {
+        match self {
+            Self::Input(_) => 3,
+            Self::Deck(error) => exit_code(error),
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::Input(message) => message.clone(),
+            Self::Deck(error) => error.to_string(),
+        }
+    }
+}
+
+/// The exit code for each `DeckCodeError` variant, as listed in `EXIT_CODES`.
+fn exit_code(error: &DeckCodeError) -> u8 {
+    match error {
+        DeckCodeError::InvalidBase64(_) => 10,
+        DeckCodeError::MissingHeaderByte { .. } => 11,
+        DeckCodeError::TruncatedSection { .. } => 12,
+        DeckCodeError::InvalidVarint { .. } => 13,
+        DeckCodeError::CountOverflow { .. } => 14,
+        DeckCodeError::InvalidSideboardMarker { .. } => 15,
+        DeckCodeError::SectionTooLarge { .. } => 16,
+        DeckCodeError::InputTooLong { .. } => 17,
+        DeckCodeError::TrailingData { .. } => 18,
+        DeckCodeError::UnknownCard { .. } => 19,
+        DeckCodeError::MissingDeckCode => 20,
+        DeckCodeError::UnknownDeckFormat { .. } => 21,
+        DeckCodeError::UnknownVersion { .. } => 22,
+        DeckCodeError::ZeroCardCount { .. } => 23,
+        DeckCodeError::CardCountOverflow { .. } => 24,
+        DeckCodeError::SideboardOwnerNotInDeck { .. } => 25,
+        DeckCodeError::CardNotInDeck { .. } => 26,
+        DeckCodeError::NotEnoughCopies { .. } => 27,
+        DeckCodeError::InvalidPatch { .. } => 28,
+        DeckCodeError::Unknown => 29,
+        #[allow(deprecated)]
+        DeckCodeError::InvalidDeckEncoding { .. } => 30,
+    }
+}
+
+/// What a command prints on success, and whether it found a problem with the deck.
+struct Output {
+    text: Vec<String>,
+    json: Value,
+    found_problem: bool,
+}
+
+/// Names cards from the card data when it is given, and by dbfid otherwise.
+struct Names<'a>(Option<&'a CardDb>);
+
+impl CardResolver for Names<'_> {
+    fn card(&self, dbfid: u32) -> Option<(String, u32)> {
+        Some(self.0.and_then(|db| db.get(dbfid)).map_or_else(
+            || (dbfid.to_string(), 0),
+            |card| (card.name.clone(), card.cost),
+        ))
+    }
+
+    fn hero_class(&self, dbfid: u32) -> Option<String> {
+        self.0
+            .map_or_else(|| Class::from_hero(dbfid), |db| db.class_of(dbfid))
+            .map(|class| class.to_string())
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // `--cards` is global, so clap cannot require it from the `validate` arguments.
+    if matches!(cli.command, Command::Validate { date: Some(_), .. }) && cli.cards.is_none() {
+        Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--date only applies to the card data, so --cards is required with it",
+            )
+            .exit();
+    }
+    match run(&cli) {
+        Ok(output) => {
+            if cli.json {
+                println!("{:#}", output.json);
+            } else {
+                for line in output.text {
+                    println!("{line}");
+                }
+            }
+            ExitCode::from(u8::from(output.found_problem))
+        }
+        Err(failure) => {
+            if cli.json {
+                let error = match &failure {
+                    Failure::Deck(error) => json!(error),
+                    Failure::Input(_) => json!("Input"),
+                };
+                eprintln!(
+                    "{:#}",
+                    json!({
+                        "error": error,
+                        "message": failure.message(),
+                        "exit_code": failure.exit_code(),
+                    })
+                );
+            } else {
+                eprintln!("error: {}", failure.message());
+            }
+            ExitCode::from(failure.exit_code())
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<Output, Failure> {
+    let db = cli
+        .cards
+        .as_deref()
+        .map(|path| {
+            CardDb::load(path).map_err(|error| {
+                Failure::Input(format!("Could not load {}: {error}", path.display()))
+            })
+        })
+        .transpose()?;
+
+    match &cli.command {
+        Command::Decode { code } => {
+            let deck = decode_deck_code(&read_code(code.as_deref())?)?;
+            Ok(decode(&deck, db.as_ref()))
+        }
+        Command::Encode { file, input } => encode(file.as_deref(), *input),
+        Command::Validate { code, date } => {
+            let code = read_code(code.as_deref())?;
+            let deck = decode_deck_code(&code)?;
+            Ok(validate(&code, &deck, db.as_ref(), *date))
+        }
+        Command::Diff { a, b } => {
+            let before = decode_deck_code(a.trim())?;
+            let after = decode_deck_code(b.trim())?;
+            compare(&before.diff(&after), &before, &after, db.as_ref())
+        }
+        Command::Canonicalize { code } => canonicalize(&read_code(code.as_deref())?),
+    }
+}
+
+/// The deck code given as an argument, or read from stdin when it is missing or `-`.
+fn read_code(code: Option<&str>) -> Result<String, Failure> {
+    match code {
+        Some(code) if code != "-" => Ok(code.trim().to_owned()),
+        _ => Ok(read_stdin()?.trim().to_owned()),
+    }
+}
+
+fn read_stdin() -> Result<String, Failure> {
+    let mut text = String::new();
+    io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|error| Failure::Input(format!("Could not read stdin: {error}")))?;
+    Ok(text)
+}
+
+fn decode(deck: &Deck, db: Option<&CardDb>) -> Output {
+    let names = Names(db);
+    let describe = |count: u8, dbfid: u32| match db.and_then(|db| db.get(dbfid)) {
+        Some(card) => format!("  {count}x ({}) {} [{dbfid}]", card.cost, card.name),
+        None => format!("  {count}x {dbfid}"),
+    };
+
+    let heroes: Vec<String> = deck
+        .heroes
+        .iter()
+        .map(|&hero| match names.hero_class(hero) {
+            Some(class) => format!("{hero} ({class})"),
+            None => hero.to_string(),
+        })
+        .collect();
+    let mut text = vec![
+        format!("Format: {}", deck.format),
+        format!("Heroes: {}", heroes.join(", ")),
+        format!("Cards: {}", deck.total_cards()),
+    ];
+    let definition = DeckDefinition::from(deck);
+    for card in &definition.cards {
+        text.push(describe(card.count, card.dbfid));
+    }
+    for sideboard in &definition.sideboards {
+        let owner = names
+            .card(sideboard.owner)
+            .map_or_else(String::new, |(name, _)| name);
+        text.push(format!("Sideboard of {owner}:"));
+        for card in &sideboard.cards {
+            text.push(describe(card.count, card.dbfid));
+        }
+    }
+
+    Output {
+        text,
+        json: json!(definition),
+        found_problem: false,
+    }
+}
+
+fn encode(file: Option<&Path>, input: Option<InputFormat>) -> Result<Output, Failure> {
+    let (text, input) = match file.filter(|path| *path != Path::new("-")) {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|error| {
+                Failure::Input(format!("Could not read {}: {error}", path.display()))
+            })?;
+            let is_toml = path
+                .extension()
+                .is_some_and(|extension| extension == "toml");
+            let default = if is_toml {
+                InputFormat::Toml
+            } else {
+                InputFormat::Json
+            };
+            (text, input.unwrap_or(default))
+        }
+        None => (read_stdin()?, input.unwrap_or(InputFormat::Json)),
+    };
+
+    let definition: DeckDefinition = match input {
+        InputFormat::Json => serde_json::from_str(&text).map_err(|error| error.to_string()),
+        InputFormat::Toml => toml::from_str(&text).map_err(|error| error.to_string()),
+    }
+    .map_err(|error| Failure::Input(format!("Invalid deck definition: {error}")))?;
+    let code = encode_deck_code(&definition.build()?);
+
+    Ok(Output {
+        text: vec![code.clone()],
+        json: json!({ "code": code }),
+        found_problem: false,
+    })
+}
+
+fn validate(code: &str, deck: &Deck, db: Option<&CardDb>, date: Option<Date>) -> Output {
+    let canonical = encode_deck_code(deck) == code;
+    let violations: Vec<String> = db.map_or_else(Vec::new, |db| {
+        let ruleset = date.map_or_else(Ruleset::default, Ruleset::for_date);
+        deck.validate(db, &ruleset)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    });
+
+    let mut text = Vec::new();
+    if violations.is_empty() {
+        text.push(format!("Valid {} deck code.", deck.format));
+    } else {
+        text.extend(violations.iter().cloned());
+    }
+    if !canonical {
+        text.push("The code is not canonical. Use `deck-codes canonicalize` to fix it.".to_owned());
+    }
+
+    Output {
+        text,
+        json: json!({
+            "format": deck.format,
+            "canonical": canonical,
+            "rules_checked": db.is_some(),
+            "violations": violations,
+        }),
+        found_problem: !violations.is_empty(),
+    }
+}
+
+fn compare(
+    diff: &DeckDiff,
+    before: &Deck,
+    after: &Deck,
+    db: Option<&CardDb>,
+) -> Result<Output, Failure> {
+    let mut text = Vec::new();
+    if diff.format_changed {
+        text.push(format!("Format: {} -> {}", before.format, after.format));
+    }
+    if diff.heroes_changed {
+        text.push(format!("Heroes: {:?} -> {:?}", before.heroes, after.heroes));
+    }
+    text.extend(diff.format_lines(&Names(db))?);
+
+    let changes = |changes: &[CardChange]| -> Vec<Value> {
+        changes
+            .iter()
+            .map(|change| {
+                json!({
+                    "dbfid": change.dbfid,
+                    "before": change.before,
+                    "after": change.after,
+                })
+            })
+            .collect()
+    };
+    let sideboards: Vec<Value> = diff
+        .sideboards
+        .iter()
+        .map(|sideboard| {
+            json!({
+                "owner": sideboard.owner,
+                "changes": changes(&sideboard.changes),
+            })
+        })
+        .collect();
+
+    Ok(Output {
+        text,
+        json: json!({
+            "format_changed": diff.format_changed,
+            "heroes_changed": diff.heroes_changed,
+            "cards": changes(&diff.cards),
+            "sideboards": sideboards,
+        }),
+        found_problem: !diff.is_empty(),
+    })
+}
+
+fn canonicalize(code: &str) -> Result<Output, Failure> {
+    let (deck, normalizations) = decode_deck_code_lenient(code)?;
+    let canonical = encode_deck_code(&deck);
+    let fixes: Vec<&str> = [
+        (normalizations.trimmed_whitespace, "trimmed_whitespace"),
+        (
+            normalizations.removed_internal_whitespace,
+            "removed_internal_whitespace",
+        ),
+        (
+            normalizations.converted_url_safe_alphabet,
+            "converted_url_safe_alphabet",
+        ),
+        (normalizations.fixed_padding, "fixed_padding"),
+    ]
+    .into_iter()
+    .filter_map(|(applied, name)| applied.then_some(name))
+    .collect();
+
+    Ok(Output {
+        text: vec![canonical.clone()],
+        json: json!({
+            "code": canonical,
+            "changed": canonical != code,
+            "normalizations": fixes,
+        }),
+        found_problem: false,
+    })
+}
+
+/// Parses a date written as `YYYY-MM-DD`.
+fn parse_date(text: &str) -> Result<Date, String> {
+    let invalid = || format!("`{text}` is not a date written as YYYY-MM-DD");
+    let mut parts = text.split('-');
+    let (Some(year), Some(month), Some(day), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let date = Date::new(
+        year.parse().map_err(|_| invalid())?,
+        month.parse().map_err(|_| invalid())?,
+        day.parse().map_err(|_| invalid())?,
+    );
+    if (1..=12).contains(&date.month) && (1..=31).contains(&date.day) {
+        Ok(date)
+    } else {
+        Err(invalid())
+    }
+}
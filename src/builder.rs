@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use crate::constants::Class;
+use crate::deck::Deck;
+use crate::error::DeckCodeError;
+use crate::format::Format;
+
+/// Builds a canonical `Deck` from heroes and `(count, dbfid)` card entries.
+#[derive(Debug)]
+pub struct DeckBuilder {
+    format: Format,
+    heroes: Vec<u32>,
+    /// Stored as tuples of (`number_of_copies`, dbfid) in insertion order.
+    cards: Vec<(u8, u32)>,
+    /// Stored as tuples of (`number_of_copies`, dbfid, owner dbfid) in insertion order.
+    sideboard_cards: Vec<(u8, u32, u32)>,
+}
+
+impl DeckBuilder {
+    /// Start building an empty deck for the given `Format`.
+    #[must_use]
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            heroes: Vec::new(),
+            cards: Vec::new(),
+            sideboard_cards: Vec::new(),
+        }
+    }
+
+    /// Add a hero by dbfid.
+    #[must_use]
+    pub fn hero(mut self, dbfid: u32) -> Self {
+        self.heroes.push(dbfid);
+        self
+    }
+
+    /// Add the default hero of `class`.
+    #[must_use]
+    pub fn class(self, class: Class) -> Self {
+        self.hero(class.default_hero())
+    }
+
+    /// Add `count` copies of the card with the given dbfid. Repeated entries for the same card are summed.
+    #[must_use]
+    pub fn card(mut self, count: u8, dbfid: u32) -> Self {
+        self.cards.push((count, dbfid));
+        self
+    }
+
+    /// Add several `(count, dbfid)` entries.
+    #[must_use]
+    pub fn cards<I: IntoIterator<Item = (u8, u32)>>(mut self, cards: I) -> Self {
+        self.cards.extend(cards);
+        self
+    }
+
+    /// Add `count` copies of a card to the sideboard of the card with dbfid `owner`.
+    /// Repeated entries for the same card and owner are summed.
+    #[must_use]
+    pub fn sideboard_card(mut self, count: u8, dbfid: u32, owner: u32) -> Self {
+        self.sideboard_cards.push((count, dbfid, owner));
+        self
+    }
+
+    /// Produce a canonical `Deck` ready for `encode_deck_code`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::ZeroCardCount` if any entry has a count of 0,
+    /// `DeckCodeError::CardCountOverflow` if the entries for a card add up to more than 255 copies and
+    /// `DeckCodeError::SideboardOwnerNotInDeck` if a sideboard card's owner is not in the deck.
+    pub fn build(self) -> Result<Deck, DeckCodeError> {
+        let mut cards: BTreeMap<u32, u8> = BTreeMap::new();
+        for (count, dbfid) in self.cards {
+            add_copies(cards.entry(dbfid).or_insert(0), count, dbfid)?;
+        }
+
+        let mut sideboard_cards: BTreeMap<(u32, u32), u8> = BTreeMap::new();
+        for (count, dbfid, owner) in self.sideboard_cards {
+            if !cards.contains_key(&owner) {
+                return Err(DeckCodeError::SideboardOwnerNotInDeck { dbfid, owner });
+            }
+            add_copies(
+                sideboard_cards.entry((dbfid, owner)).or_insert(0),
+                count,
+                dbfid,
+            )?;
+        }
+
+        let cards: Vec<(u8, u32)> = cards
+            .into_iter()
+            .map(|(dbfid, count)| (count, dbfid))
+            .collect();
+        let sideboard_cards = sideboard_cards
+            .into_iter()
+            .map(|((dbfid, owner), count)| (dbfid, count, owner))
+            .collect();
+
+        Ok(Deck::from_cards(
+            self.format,
+            self.heroes,
+            &cards,
+            sideboard_cards,
+        ))
+    }
+}
+
+fn add_copies(total: &mut u8, count: u8, dbfid: u32) -> Result<(), DeckCodeError> {
+    if count == 0 {
+        return Err(DeckCodeError::ZeroCardCount { dbfid });
+    }
+    *total = total
+        .checked_add(count)
+        .ok_or(DeckCodeError::CardCountOverflow { dbfid })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_deck_code;
+
+    #[test]
+    fn build_places_cards_into_sections_and_sorts_them() {
+        let deck = DeckBuilder::new(Format::Wild)
+            .hero(7)
+            .cards([(3, 4), (1, 9), (2, 5), (1, 2)])
+            .build()
+            .expect("Valid deck");
+        assert_eq!(
+            deck.cards(),
+            vec![(1, 2, None), (1, 9, None), (2, 5, None), (3, 4, None)]
+        );
+    }
+
+    #[test]
+    fn class_adds_default_hero() {
+        let deck = DeckBuilder::new(Format::Standard)
+            .class(Class::DeathKnight)
+            .build()
+            .expect("Valid deck");
+        assert_eq!(deck.heroes, vec![78065]);
+        assert_eq!(deck.class(), Some(Class::DeathKnight));
+    }
+
+    #[test]
+    fn build_sums_repeated_cards() {
+        let deck = DeckBuilder::new(Format::Wild)
+            .hero(7)
+            .card(1, 5)
+            .card(1, 5)
+            .card(2, 5)
+            .build()
+            .expect("Valid deck");
+        assert_eq!(deck.cards(), vec![(4, 5, None)]);
+    }
+
+    #[test]
+    fn build_matches_decoded_sideboard_deck() {
+        let code = "AAECAZICBp/zBamVBvajBsekBtrBBoviBgyunwSaoAagoAaHqAbvqQbDugbQygbzygaL3Aad4wb75Qad6wYAAQP0swbHpAb3swbHpAbo3gbHpAYAAA==";
+        let decoded = decode_deck_code(code).expect("Decoded correctly");
+
+        let mut builder = DeckBuilder::new(Format::Standard).hero(274);
+        for (count, dbfid, owner) in decoded.cards().into_iter().rev() {
+            builder = match owner {
+                Some(owner) => builder.sideboard_card(count, dbfid, owner),
+                None => builder.card(count, dbfid),
+            };
+        }
+        assert_eq!(builder.build(), Ok(decoded));
+    }
+
+    #[test]
+    fn build_returns_err_for_zero_count() {
+        let result = DeckBuilder::new(Format::Wild).hero(7).card(0, 5).build();
+        assert_eq!(result, Err(DeckCodeError::ZeroCardCount { dbfid: 5 }));
+    }
+
+    #[test]
+    fn build_returns_err_for_count_overflow() {
+        let result = DeckBuilder::new(Format::Wild)
+            .hero(7)
+            .card(255, 5)
+            .card(1, 5)
+            .build();
+        assert_eq!(result, Err(DeckCodeError::CardCountOverflow { dbfid: 5 }));
+    }
+
+    #[test]
+    fn build_returns_err_for_sideboard_owner_not_in_deck() {
+        let result = DeckBuilder::new(Format::Standard)
+            .hero(274)
+            .card(1, 5)
+            .sideboard_card(1, 6, 7)
+            .build();
+        assert_eq!(
+            result,
+            Err(DeckCodeError::SideboardOwnerNotInDeck { dbfid: 6, owner: 7 })
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::constants::Class;
+use crate::deck::Deck;
+use crate::error::{CardDbError, DeckCodeError};
+use crate::export::CardResolver;
+
+/// The rarity of a card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Rarity {
+    Free,
+    Common,
+    Rare,
+    Epic,
+    Legendary,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The type of a card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CardType {
+    Minion,
+    Spell,
+    Weapon,
+    Location,
+    Hero,
+    HeroPower,
+    Enchantment,
+    #[serde(other)]
+    Unknown,
+}
+
+/// A card as described by [HearthstoneJSON](https://hearthstonejson.com/).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Card {
+    pub dbf_id: u32,
+    /// The card id, e.g. `EX1_295`
+    pub id: String,
+    #[serde(deserialize_with = "deserialize_name")]
+    pub name: String,
+    #[serde(default)]
+    pub cost: u32,
+    /// The class of the card as written by `HearthstoneJSON`, e.g. `MAGE` or `NEUTRAL`
+    #[serde(default)]
+    pub card_class: String,
+    /// Every class that can play the card, for cards belonging to more than one class
+    #[serde(default)]
+    pub classes: Vec<String>,
+    pub rarity: Option<Rarity>,
+    /// The set the card belongs to, e.g. `EXPERT1`
+    #[serde(default)]
+    pub set: String,
+    #[serde(rename = "type")]
+    pub card_type: CardType,
+    #[serde(default)]
+    pub collectible: bool,
+    #[serde(default)]
+    pub mechanics: Vec<String>,
+}
+
+/// A deck entry resolved to card data, as a tuple of (`number_of_copies`, card, sideboard owner).
+pub type ResolvedCard<'a> = (u8, &'a Card, Option<&'a Card>);
+
+/// A collection of cards indexed by dbfid.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CardDb {
+    cards: HashMap<u32, Card>,
+}
+
+impl CardDb {
+    /// Load a `cards.json` or `cards.collectible.json` file downloaded from `HearthstoneJSON`.
+    /// Both the single locale files and the `all` locales files are supported; names are read in `enUS`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a `HearthstoneJSON` card list.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CardDbError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a `HearthstoneJSON` card list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be read or is not a `HearthstoneJSON` card list.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, CardDbError> {
+        let cards: Vec<Card> = serde_json::from_reader(reader)?;
+        Ok(cards.into_iter().collect())
+    }
+
+    /// Parse a `HearthstoneJSON` card list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not a `HearthstoneJSON` card list.
+    pub fn from_json(json: &str) -> Result<Self, CardDbError> {
+        let cards: Vec<Card> = serde_json::from_str(json)?;
+        Ok(cards.into_iter().collect())
+    }
+
+    /// The card with the given dbfid.
+    #[must_use]
+    pub fn get(&self, dbf_id: u32) -> Option<&Card> {
+        self.cards.get(&dbf_id)
+    }
+
+    /// The class of the hero with the given dbfid, from its card data. Heroes missing from the database, or without a
+    /// class in it, fall back to `constants::HEROES`.
+    #[must_use]
+    pub fn class_of(&self, hero: u32) -> Option<Class> {
+        self.get(hero)
+            .and_then(|card| Class::from_card_class(&card.card_class))
+            .or_else(|| Class::from_hero(hero))
+    }
+
+    /// The number of cards in the database.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Every card in the database, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Card> {
+        self.cards.values()
+    }
+
+    /// Resolve the result of `Deck::cards()` to card data.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::UnknownCard` if a card or sideboard owner is not in the database.
+    pub fn resolve(&self, deck: &Deck) -> Result<Vec<ResolvedCard<'_>>, DeckCodeError> {
+        let lookup = |dbfid: u32| self.get(dbfid).ok_or(DeckCodeError::UnknownCard { dbfid });
+        deck.cards()
+            .into_iter()
+            .map(|(count, dbfid, owner)| {
+                Ok((count, lookup(dbfid)?, owner.map(lookup).transpose()?))
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<Card> for CardDb {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        Self {
+            cards: cards.into_iter().map(|card| (card.dbf_id, card)).collect(),
+        }
+    }
+}
+
+impl CardResolver for CardDb {
+    fn card(&self, dbfid: u32) -> Option<(String, u32)> {
+        self.get(dbfid).map(|card| (card.name.clone(), card.cost))
+    }
+
+    fn hero_class(&self, dbfid: u32) -> Option<String> {
+        self.class_of(dbfid).map(|class| class.to_string())
+    }
+}
+
+impl Deck {
+    /// The class of the deck's first hero, as `CardDb::class_of` finds it. Unlike `Deck::class`, this knows every
+    /// hero skin in the card data.
+    #[must_use]
+    pub fn class_in(&self, db: &CardDb) -> Option<Class> {
+        self.heroes.first().and_then(|&hero| db.class_of(hero))
+    }
+}
+
+/// Card names are either a string, or an object of names by locale in the `all` locales files.
+fn deserialize_name<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Name {
+        Single(String),
+        Localized(HashMap<String, String>),
+    }
+
+    match Name::deserialize(deserializer)? {
+        Name::Single(name) => Ok(name),
+        Name::Localized(mut names) => names
+            .remove("enUS")
+            .ok_or_else(|| serde::de::Error::missing_field("enUS")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    const CARDS: &str = r#"[
+        {"cardClass":"MAGE","collectible":true,"cost":3,"dbfId":192,"id":"EX1_295","mechanics":["SECRET"],"name":"Ice Block","rarity":"EPIC","set":"LEGACY","type":"SPELL"},
+        {"cardClass":"MAGE","collectible":true,"cost":0,"dbfId":637,"id":"HERO_08","name":"Jaina Proudmoore","rarity":"FREE","set":"LEGACY","type":"HERO"},
+        {"cardClass":"NEUTRAL","cost":1,"dbfId":1,"id":"GAME_005","name":{"enUS":"The Coin","deDE":"Die Münze"},"set":"CORE","type":"SPELL"},
+        {"cardClass":"NEUTRAL","dbfId":2,"id":"XXX_001e","name":"Enchanted","set":"CORE","type":"ENCHANTMENT","rarity":"SOMETHING_NEW"},
+        {"cardClass":"DRUID","collectible":true,"dbfId":99001,"id":"HERO_06x","name":"Hero Skin","set":"HERO_SKINS","type":"HERO"}
+    ]"#;
+
+    #[test]
+    fn from_json_reads_hearthstonejson_cards() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        assert_eq!(db.len(), 5);
+
+        let ice_block = db.get(192).expect("Ice Block is present");
+        assert_eq!(ice_block.name, "Ice Block");
+        assert_eq!(ice_block.cost, 3);
+        assert_eq!(ice_block.rarity, Some(Rarity::Epic));
+        assert_eq!(ice_block.card_type, CardType::Spell);
+        assert_eq!(ice_block.mechanics, vec!["SECRET".to_owned()]);
+        assert!(ice_block.collectible);
+    }
+
+    #[test]
+    fn from_json_reads_localized_names_and_unknown_values() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let coin = db.get(1).expect("The Coin is present");
+        assert_eq!(coin.name, "The Coin");
+        assert_eq!(coin.rarity, None);
+        assert!(!coin.collectible);
+        assert_eq!(
+            db.get(2).map(|card| card.rarity),
+            Some(Some(Rarity::Unknown))
+        );
+    }
+
+    #[test]
+    fn from_json_returns_err_for_invalid_json() {
+        assert!(CardDb::from_json("{\"cards\": []}").is_err());
+    }
+
+    #[test]
+    fn resolve_maps_deck_cards_to_card_data() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let deck = Deck::from_cards(Format::Wild, vec![637], &[(2, 192)], Vec::new());
+        let resolved = db.resolve(&deck).expect("Resolved");
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].0, 2);
+        assert_eq!(resolved[0].1.name, "Ice Block");
+
+        let deck = Deck::from_cards(Format::Wild, vec![637], &[(1, 9)], Vec::new());
+        assert_eq!(
+            db.resolve(&deck),
+            Err(DeckCodeError::UnknownCard { dbfid: 9 })
+        );
+    }
+
+    #[test]
+    fn hero_class_is_written_as_in_client() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        assert_eq!(db.hero_class(637).as_deref(), Some("Mage"));
+    }
+
+    #[test]
+    fn class_of_reads_card_data_before_hero_table() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        // A skin missing from `HEROES`
+        assert_eq!(Class::from_hero(99001), None);
+        assert_eq!(db.class_of(99001), Some(Class::Druid));
+        // A hero missing from the card data
+        assert_eq!(db.class_of(7), Some(Class::Warrior));
+        assert_eq!(db.class_of(1), None);
+
+        let deck = Deck::from_cards(Format::Wild, vec![99001], &[(1, 192)], Vec::new());
+        assert_eq!(deck.class(), None);
+        assert_eq!(deck.class_in(&db), Some(Class::Druid));
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "cards")]
+use serde::Deserialize;
+
+#[cfg(feature = "cards")]
+use crate::cards::CardDb;
+use crate::deck::Deck;
+#[cfg(feature = "cards")]
+use crate::dust::{is_given_to_every_player, OwnedCards};
+use crate::error::CollectionError;
+#[cfg(feature = "cards")]
+use crate::error::DeckCodeError;
+
+/// The copies of a card a player owns, by finish.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cards", derive(Deserialize))]
+pub struct OwnedCopies {
+    #[cfg_attr(feature = "cards", serde(default))]
+    pub normal: u8,
+    #[cfg_attr(feature = "cards", serde(default))]
+    pub golden: u8,
+    #[cfg_attr(feature = "cards", serde(default))]
+    pub signature: u8,
+    #[cfg_attr(feature = "cards", serde(default))]
+    pub diamond: u8,
+}
+
+impl OwnedCopies {
+    /// The number of copies owned in any finish.
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        u32::from(self.normal)
+            + u32::from(self.golden)
+            + u32::from(self.signature)
+            + u32::from(self.diamond)
+    }
+}
+
+/// The cards a player owns, indexed by dbfid.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Collection {
+    cards: HashMap<u32, OwnedCopies>,
+}
+
+/// An entry of a JSON collection file.
+#[cfg(feature = "cards")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CollectionEntry {
+    dbf_id: u32,
+    #[serde(flatten)]
+    copies: OwnedCopies,
+}
+
+impl Collection {
+    /// Read a JSON collection file, a list of objects such as `{"dbfId": 113, "normal": 2, "golden": 1}`.
+    /// Finishes that are left out are not owned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a collection.
+    #[cfg(feature = "cards")]
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Self, CollectionError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Parse a JSON collection, as described in `Collection::load_json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not a collection.
+    #[cfg(feature = "cards")]
+    pub fn from_json(json: &str) -> Result<Self, CollectionError> {
+        let entries: Vec<CollectionEntry> = serde_json::from_str(json)?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.dbf_id, entry.copies))
+            .collect())
+    }
+
+    /// Read a CSV collection file with the columns `dbfId,normal,golden,signature,diamond`.
+    /// A header line is skipped, and missing trailing columns are not owned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or a line is not a collection entry.
+    pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Self, CollectionError> {
+        Self::from_csv(&fs::read_to_string(path)?)
+    }
+
+    /// Parse a CSV collection, as described in `Collection::load_csv`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CollectionError::InvalidCsvLine` if a line is not a collection entry.
+    pub fn from_csv(csv: &str) -> Result<Self, CollectionError> {
+        let mut collection = Self::default();
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || (index == 0 && line.starts_with(|c: char| c.is_alphabetic())) {
+                continue;
+            }
+            let (dbfid, copies) =
+                parse_csv_line(line).ok_or(CollectionError::InvalidCsvLine { line: index + 1 })?;
+            collection.insert(dbfid, copies);
+        }
+        Ok(collection)
+    }
+
+    /// Set the copies owned of the card with the given dbfid.
+    pub fn insert(&mut self, dbfid: u32, copies: OwnedCopies) {
+        self.cards.insert(dbfid, copies);
+    }
+
+    /// The copies owned of the card with the given dbfid, or `None` if the player has none.
+    #[must_use]
+    pub fn get(&self, dbfid: u32) -> Option<&OwnedCopies> {
+        self.cards.get(&dbfid)
+    }
+
+    /// The number of copies owned of the card with the given dbfid, in any finish.
+    #[must_use]
+    pub fn copies_of(&self, dbfid: u32) -> u32 {
+        self.get(dbfid).map_or(0, OwnedCopies::total)
+    }
+
+    /// The number of different cards in the collection.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// The copies of cards in `deck`, including sideboards, that are not in the collection.
+    /// Returned as tuples of (`number_of_copies`, dbfid), sorted by dbfid.
+    ///
+    /// This is the raw difference between the deck and the collection, so it includes free and Core set cards that
+    /// every player owns without them being in a collection export. Use `Collection::missing_for_in` to leave them out.
+    #[must_use]
+    pub fn missing_for(&self, deck: &Deck) -> Vec<(u8, u32)> {
+        deck.needed_copies()
+            .into_iter()
+            .filter_map(|(dbfid, copies)| {
+                let missing = copies.saturating_sub(self.copies_of(dbfid));
+                (missing > 0).then(|| (u8::try_from(missing).unwrap_or(u8::MAX), dbfid))
+            })
+            .collect()
+    }
+
+    /// The copies of cards in `deck`, including sideboards, that the player does not own, leaving out the free and
+    /// Core set cards every player owns. Returned as tuples of (`number_of_copies`, dbfid), sorted by dbfid.
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::UnknownCard` if a missing card is not in the database.
+    #[cfg(feature = "cards")]
+    pub fn missing_for_in(
+        &self,
+        deck: &Deck,
+        db: &CardDb,
+    ) -> Result<Vec<(u8, u32)>, DeckCodeError> {
+        let mut missing = Vec::new();
+        for (copies, dbfid) in self.missing_for(deck) {
+            let card = db.get(dbfid).ok_or(DeckCodeError::UnknownCard { dbfid })?;
+            if !is_given_to_every_player(card) {
+                missing.push((copies, dbfid));
+            }
+        }
+        Ok(missing)
+    }
+}
+
+impl FromIterator<(u32, OwnedCopies)> for Collection {
+    fn from_iter<I: IntoIterator<Item = (u32, OwnedCopies)>>(cards: I) -> Self {
+        Self {
+            cards: cards.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(feature = "cards")]
+impl OwnedCards for Collection {
+    fn owned_copies(&self, dbfid: u32) -> u32 {
+        self.copies_of(dbfid)
+    }
+}
+
+/// Parses `113,2,1,0,0` into a dbfid and the copies owned.
+fn parse_csv_line(line: &str) -> Option<(u32, OwnedCopies)> {
+    let mut fields = line.split(',').map(str::trim);
+    let dbfid = fields.next()?.parse().ok()?;
+    let mut counts = [0; 4];
+    for count in &mut counts {
+        match fields.next() {
+            Some(field) if !field.is_empty() => *count = field.parse().ok()?,
+            _ => {}
+        }
+    }
+    if fields.next().is_some() {
+        return None;
+    }
+    let [normal, golden, signature, diamond] = counts;
+    Some((
+        dbfid,
+        OwnedCopies {
+            normal,
+            golden,
+            signature,
+            diamond,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    fn copies(normal: u8, golden: u8) -> OwnedCopies {
+        OwnedCopies {
+            normal,
+            golden,
+            ..OwnedCopies::default()
+        }
+    }
+
+    #[test]
+    fn from_csv_reads_entries_and_skips_header() {
+        let collection = Collection::from_csv(
+            "dbfId,normal,golden,signature,diamond\n113,1,1,0,0\n192,0,0,0,1\n315,2\n",
+        )
+        .expect("Parsed correctly");
+        assert_eq!(collection.len(), 3);
+        assert_eq!(collection.get(113), Some(&copies(1, 1)));
+        assert_eq!(collection.copies_of(192), 1);
+        assert_eq!(collection.get(315), Some(&copies(2, 0)));
+        assert_eq!(collection.copies_of(662), 0);
+    }
+
+    #[test]
+    fn from_csv_returns_err_for_invalid_line() {
+        assert!(matches!(
+            Collection::from_csv("113,1\n192,one\n"),
+            Err(CollectionError::InvalidCsvLine { line: 2 })
+        ));
+    }
+
+    #[cfg(feature = "cards")]
+    #[test]
+    fn from_json_reads_entries() {
+        let collection =
+            Collection::from_json(r#"[{"dbfId": 113, "normal": 2, "golden": 1}, {"dbfId": 192}]"#)
+                .expect("Parsed correctly");
+        assert_eq!(collection.get(113), Some(&copies(2, 1)));
+        assert_eq!(collection.copies_of(192), 0);
+    }
+
+    #[test]
+    fn missing_for_lists_unowned_copies() {
+        let collection: Collection = [(113, copies(1, 0)), (192, copies(0, 1)), (4, copies(1, 0))]
+            .into_iter()
+            .collect();
+        let deck = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(2, 113), (1, 192), (2, 315), (1, 4)],
+            vec![(4, 1, 315)],
+        );
+        assert_eq!(
+            collection.missing_for(&deck),
+            vec![(1, 4), (1, 113), (2, 315)]
+        );
+    }
+
+    #[cfg(feature = "cards")]
+    #[test]
+    fn missing_for_in_leaves_out_cards_every_player_owns() {
+        let db = CardDb::from_json(
+            r#"[
+                {"cardClass":"MAGE","collectible":true,"dbfId":113,"id":"A","name":"Core","rarity":"COMMON","set":"CORE","type":"SPELL"},
+                {"cardClass":"MAGE","collectible":true,"dbfId":192,"id":"B","name":"Free","rarity":"FREE","set":"LEGACY","type":"SPELL"},
+                {"cardClass":"MAGE","collectible":true,"dbfId":315,"id":"C","name":"Epic","rarity":"EPIC","set":"EXPERT1","type":"SPELL"}
+            ]"#,
+        )
+        .expect("Parsed correctly");
+        let deck = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(2, 113), (1, 192), (2, 315)],
+            Vec::new(),
+        );
+        let collection = Collection::default();
+        assert_eq!(collection.missing_for(&deck).len(), 3);
+        assert_eq!(collection.missing_for_in(&deck, &db), Ok(vec![(2, 315)]));
+
+        let deck = Deck::from_cards(Format::Standard, vec![637], &[(1, 4)], Vec::new());
+        assert_eq!(
+            collection.missing_for_in(&deck, &db),
+            Err(DeckCodeError::UnknownCard { dbfid: 4 })
+        );
+    }
+}
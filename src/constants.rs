@@ -1 +1,176 @@
-pub CURRENT_VERSION: u8 = 1;
\ No newline at end of file
+use std::fmt;
+
+/// The deck code version written by `encode_deck_code`, and the only version that can be decoded.
+pub const CURRENT_VERSION: u8 = 1;
+
+/// The class of a hero, and so of the deck it leads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Class {
+    DeathKnight,
+    DemonHunter,
+    Druid,
+    Hunter,
+    Mage,
+    Paladin,
+    Priest,
+    Rogue,
+    Shaman,
+    Warlock,
+    Warrior,
+}
+
+/// The dbfid of each class's original hero and of some alternate hero skins, with the class they belong to.
+/// The list is partial, so `CardDb::class_of` reads the class from card data first and only falls back to this table.
+/// Sorted by dbfid.
+pub const HEROES: &[(u32, Class)] = &[
+    (7, Class::Warrior),
+    (31, Class::Hunter),
+    (274, Class::Druid),
+    (637, Class::Mage),
+    (671, Class::Paladin),
+    (813, Class::Priest),
+    (893, Class::Warlock),
+    (930, Class::Rogue),
+    (1066, Class::Shaman),
+    (2826, Class::Hunter),   // Alleria Windrunner
+    (2827, Class::Paladin),  // Lady Liadrin
+    (2828, Class::Warrior),  // Magni Bronzebeard
+    (2829, Class::Mage),     // Khadgar
+    (39117, Class::Mage),    // Medivh
+    (40183, Class::Shaman),  // Morgl the Oracle
+    (40195, Class::Rogue),   // Maiev Shadowsong
+    (41887, Class::Priest),  // Tyrande Whisperwind
+    (46116, Class::Paladin), // Prince Arthas
+    (47817, Class::Warlock), // Nemsy Necrofizzle
+    (50484, Class::Druid),   // Lunara
+    (56550, Class::DemonHunter),
+    (78065, Class::DeathKnight),
+];
+
+impl Class {
+    /// Every class, in alphabetical order.
+    pub const ALL: [Self; 11] = [
+        Self::DeathKnight,
+        Self::DemonHunter,
+        Self::Druid,
+        Self::Hunter,
+        Self::Mage,
+        Self::Paladin,
+        Self::Priest,
+        Self::Rogue,
+        Self::Shaman,
+        Self::Warlock,
+        Self::Warrior,
+    ];
+
+    /// The class of the hero with the given dbfid, or `None` if the hero is not in `HEROES`.
+    #[must_use]
+    pub fn from_hero(dbfid: u32) -> Option<Self> {
+        HEROES
+            .binary_search_by_key(&dbfid, |&(hero, _)| hero)
+            .ok()
+            .and_then(|index| HEROES.get(index))
+            .map(|&(_, class)| class)
+    }
+
+    /// Returns a matching `Class` from a class as written by `HearthstoneJSON`, e.g. `DEATHKNIGHT`.
+    #[must_use]
+    pub fn from_card_class(card_class: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|class| class.card_class() == card_class)
+    }
+
+    /// The class as written by `HearthstoneJSON`, e.g. `DEATHKNIGHT`.
+    #[must_use]
+    pub fn card_class(self) -> &'static str {
+        match self {
+            Self::DeathKnight => "DEATHKNIGHT",
+            Self::DemonHunter => "DEMONHUNTER",
+            Self::Druid => "DRUID",
+            Self::Hunter => "HUNTER",
+            Self::Mage => "MAGE",
+            Self::Paladin => "PALADIN",
+            Self::Priest => "PRIEST",
+            Self::Rogue => "ROGUE",
+            Self::Shaman => "SHAMAN",
+            Self::Warlock => "WARLOCK",
+            Self::Warrior => "WARRIOR",
+        }
+    }
+
+    /// The dbfid of the class's original hero, used when building a deck for the class.
+    #[must_use]
+    pub fn default_hero(self) -> u32 {
+        match self {
+            Self::DeathKnight => 78065, // The Lich King
+            Self::DemonHunter => 56550, // Illidan Stormrage
+            Self::Druid => 274,         // Malfurion Stormrage
+            Self::Hunter => 31,         // Rexxar
+            Self::Mage => 637,          // Jaina Proudmoore
+            Self::Paladin => 671,       // Uther Lightbringer
+            Self::Priest => 813,        // Anduin Wrynn
+            Self::Rogue => 930,         // Valeera Sanguinar
+            Self::Shaman => 1066,       // Thrall
+            Self::Warlock => 893,       // Gul'dan
+            Self::Warrior => 7,         // Garrosh Hellscream
+        }
+    }
+}
+
+impl fmt::Display for Class {
+    /// The name of the class as shown by the Hearthstone client.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::DeathKnight => "Death Knight",
+            Self::DemonHunter => "Demon Hunter",
+            Self::Druid => "Druid",
+            Self::Hunter => "Hunter",
+            Self::Mage => "Mage",
+            Self::Paladin => "Paladin",
+            Self::Priest => "Priest",
+            Self::Rogue => "Rogue",
+            Self::Shaman => "Shaman",
+            Self::Warlock => "Warlock",
+            Self::Warrior => "Warrior",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heroes_are_sorted_by_dbfid() {
+        assert!(HEROES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn from_hero_finds_base_heroes_and_skins() {
+        assert_eq!(Class::from_hero(274), Some(Class::Druid));
+        assert_eq!(Class::from_hero(637), Some(Class::Mage));
+        assert_eq!(Class::from_hero(930), Some(Class::Rogue));
+        assert_eq!(Class::from_hero(78065), Some(Class::DeathKnight));
+        assert_eq!(Class::from_hero(2829), Some(Class::Mage));
+        assert_eq!(Class::from_hero(1), None);
+    }
+
+    #[test]
+    fn default_hero_belongs_to_class() {
+        for class in Class::ALL {
+            assert_eq!(Class::from_hero(class.default_hero()), Some(class));
+        }
+    }
+
+    #[test]
+    fn from_card_class_reads_hearthstonejson_classes() {
+        assert_eq!(
+            Class::from_card_class("DEMONHUNTER"),
+            Some(Class::DemonHunter)
+        );
+        assert_eq!(Class::from_card_class("NEUTRAL"), None);
+        assert_eq!(Class::DemonHunter.to_string(), "Demon Hunter");
+    }
+}
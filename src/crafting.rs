@@ -0,0 +1,340 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use crate::cards::CardDb;
+use crate::collection::Collection;
+use crate::deck::Deck;
+use crate::dust::{crafting_cost, is_craftable};
+use crate::error::DeckCodeError;
+
+/// Up to this many target decks, every combination of decks is tried. Beyond it decks are chosen greedily.
+const EXACT_SEARCH_LIMIT: usize = 12;
+
+/// A deck the player wants to build.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CraftingTarget<'a> {
+    pub deck: &'a Deck,
+    /// Higher priorities are preferred when the budget cannot complete every deck.
+    pub priority: u32,
+}
+
+/// The decks that can be completed within a dust budget and the crafts needed to complete them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CraftingPlan {
+    /// Indices into the targets of the decks that are completed by the plan, highest priority first.
+    pub completed: Vec<usize>,
+    /// The cards to craft as (`number_of_copies`, dbfid), in the order of the decks they complete.
+    pub crafts: Vec<(u8, u32)>,
+    /// The dust the crafts cost.
+    pub dust: u32,
+    /// Whether the plan completes each target, in the order of the targets.
+    pub decks: Vec<DeckCompletion>,
+}
+
+/// Whether a `CraftingPlan` completes a target deck.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeckCompletion {
+    /// The plan's crafts complete the deck.
+    Completed,
+    /// The deck can be completed by crafting, but not within the budget alongside the completed decks.
+    /// `dust` is what completing it alone would cost.
+    OverBudget { dust: u32 },
+    /// The deck is missing cards that cannot be crafted or owned for free, as (`number_of_copies`, dbfid), so no
+    /// budget completes it.
+    Uncraftable { cards: Vec<(u8, u32)> },
+}
+
+/// The copies of each card that have to be crafted to complete a deck, with the dust each copy costs.
+type Needs = BTreeMap<u32, (u32, u32)>;
+
+/// Plan which of `targets` to complete with at most `budget` dust, given the cards already in `collection`.
+///
+/// Copies crafted for one deck are used by every other deck, so decks sharing cards are cheaper to complete together.
+/// The plan completes as many decks as possible, preferring higher total priority and then lower cost.
+/// Free and Core set cards are owned by every player. A deck missing any other card that cannot be crafted is never
+/// completed.
+/// # Errors
+///
+/// Returns `DeckCodeError::UnknownCard` if a card in a target deck is not in the database.
+pub fn plan_crafting(
+    db: &CardDb,
+    collection: &Collection,
+    targets: &[CraftingTarget],
+    budget: u32,
+) -> Result<CraftingPlan, DeckCodeError> {
+    let (needs, uncraftable): (Vec<Needs>, Vec<Vec<(u8, u32)>>) = targets
+        .iter()
+        .map(|target| crafting_needs(db, collection, target.deck))
+        .collect::<Result<Vec<_>, DeckCodeError>>()?
+        .into_iter()
+        .unzip();
+    let blocked: Vec<bool> = uncraftable.iter().map(|cards| !cards.is_empty()).collect();
+
+    let mut chosen = if targets.len() <= EXACT_SEARCH_LIMIT {
+        best_combination(targets, &needs, &blocked, budget)
+    } else {
+        greedy_combination(targets, &needs, &blocked, budget)
+    };
+    chosen.sort_by_key(|&index| (Reverse(targets[index].priority), index));
+
+    let mut crafted: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut plan = CraftingPlan::default();
+    for &index in &chosen {
+        for (&dbfid, &(copies, dust)) in &needs[index] {
+            let already_crafted = crafted.entry(dbfid).or_insert(0);
+            if copies > *already_crafted {
+                let extra = copies - *already_crafted;
+                *already_crafted = copies;
+                plan.crafts
+                    .push((u8::try_from(extra).unwrap_or(u8::MAX), dbfid));
+                plan.dust += extra * dust;
+            }
+        }
+    }
+    plan.decks = uncraftable
+        .into_iter()
+        .enumerate()
+        .map(|(index, cards)| {
+            if !cards.is_empty() {
+                DeckCompletion::Uncraftable { cards }
+            } else if chosen.contains(&index) {
+                DeckCompletion::Completed
+            } else {
+                DeckCompletion::OverBudget {
+                    dust: combined_cost(&needs, &[index]),
+                }
+            }
+        })
+        .collect();
+    plan.completed = chosen;
+    Ok(plan)
+}
+
+/// The cards that have to be crafted to complete `deck`, and the missing cards that cannot be crafted or owned for free.
+fn crafting_needs(
+    db: &CardDb,
+    collection: &Collection,
+    deck: &Deck,
+) -> Result<(Needs, Vec<(u8, u32)>), DeckCodeError> {
+    let mut needs = Needs::new();
+    let mut uncraftable = Vec::new();
+    for (copies, dbfid) in collection.missing_for_in(deck, db)? {
+        let card = db.get(dbfid).ok_or(DeckCodeError::UnknownCard { dbfid })?;
+        match card.rarity.filter(|_| is_craftable(card)) {
+            Some(rarity) => {
+                needs.insert(dbfid, (u32::from(copies), crafting_cost(rarity)));
+            }
+            None => uncraftable.push((copies, dbfid)),
+        }
+    }
+    Ok((needs, uncraftable))
+}
+
+/// The dust needed to complete every deck in `chosen` together.
+fn combined_cost(needs: &[Needs], chosen: &[usize]) -> u32 {
+    let mut copies: BTreeMap<u32, (u32, u32)> = BTreeMap::new();
+    for &index in chosen {
+        for (&dbfid, &(needed, dust)) in &needs[index] {
+            let entry = copies.entry(dbfid).or_insert((0, dust));
+            entry.0 = entry.0.max(needed);
+        }
+    }
+    copies.values().map(|&(needed, dust)| needed * dust).sum()
+}
+
+/// Try every combination of decks that are not `blocked` and keep the best one within the budget.
+fn best_combination(
+    targets: &[CraftingTarget],
+    needs: &[Needs],
+    blocked: &[bool],
+    budget: u32,
+) -> Vec<usize> {
+    let mut best: Vec<usize> = Vec::new();
+    let mut best_score = (0, 0, Reverse(0));
+    for mask in 1_u32..(1 << targets.len()) {
+        let chosen: Vec<usize> = (0..targets.len())
+            .filter(|index| mask & (1 << index) != 0)
+            .collect();
+        if chosen.iter().any(|&index| blocked[index]) {
+            continue;
+        }
+        let cost = combined_cost(needs, &chosen);
+        if cost > budget {
+            continue;
+        }
+        let priority: u64 = chosen
+            .iter()
+            .map(|&index| u64::from(targets[index].priority))
+            .sum();
+        let score = (chosen.len(), priority, Reverse(cost));
+        if score > best_score {
+            best_score = score;
+            best = chosen;
+        }
+    }
+    best
+}
+
+/// Repeatedly add the deck that is not `blocked` and cheapest to complete on top of those already chosen.
+fn greedy_combination(
+    targets: &[CraftingTarget],
+    needs: &[Needs],
+    blocked: &[bool],
+    budget: u32,
+) -> Vec<usize> {
+    let mut chosen: Vec<usize> = Vec::new();
+    loop {
+        let next = (0..targets.len())
+            .filter(|&index| !blocked[index] && !chosen.contains(&index))
+            .filter_map(|index| {
+                let mut candidate = chosen.clone();
+                candidate.push(index);
+                let cost = combined_cost(needs, &candidate);
+                (cost <= budget).then_some((cost, Reverse(targets[index].priority), index))
+            })
+            .min();
+        match next {
+            Some((_, _, index)) => chosen.push(index),
+            None => return chosen,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::OwnedCopies;
+    use crate::format::Format;
+
+    const CARDS: &str = r#"[
+        {"cardClass":"NEUTRAL","collectible":true,"dbfId":1,"id":"A","name":"Legendary","rarity":"LEGENDARY","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"NEUTRAL","collectible":true,"dbfId":2,"id":"B","name":"Epic","rarity":"EPIC","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"NEUTRAL","collectible":true,"dbfId":3,"id":"C","name":"Rare","rarity":"RARE","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"NEUTRAL","collectible":true,"dbfId":4,"id":"D","name":"Common","rarity":"COMMON","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"NEUTRAL","collectible":true,"dbfId":5,"id":"E","name":"Core","rarity":"COMMON","set":"CORE","type":"MINION"},
+        {"cardClass":"NEUTRAL","collectible":false,"dbfId":6,"id":"F","name":"Reward","rarity":"LEGENDARY","set":"EXPERT1","type":"MINION"}
+    ]"#;
+
+    fn deck(cards: &[(u8, u32)]) -> Deck {
+        Deck::from_cards(Format::Wild, vec![7], cards, Vec::new())
+    }
+
+    fn setup() -> (CardDb, Collection) {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let collection: Collection = [(
+            4,
+            OwnedCopies {
+                normal: 1,
+                ..OwnedCopies::default()
+            },
+        )]
+        .into_iter()
+        .collect();
+        (db, collection)
+    }
+
+    #[test]
+    fn shared_cards_are_crafted_once() {
+        let (db, collection) = setup();
+        let first = deck(&[(1, 1), (2, 4), (2, 5)]);
+        let second = deck(&[(1, 1), (2, 3)]);
+        let targets = [
+            CraftingTarget {
+                deck: &first,
+                priority: 1,
+            },
+            CraftingTarget {
+                deck: &second,
+                priority: 2,
+            },
+        ];
+        let plan = plan_crafting(&db, &collection, &targets, 1840).expect("Known cards");
+        assert_eq!(plan.completed, vec![1, 0]);
+        assert_eq!(plan.crafts, vec![(1, 1), (2, 3), (1, 4)]);
+        assert_eq!(plan.dust, 1840);
+    }
+
+    #[test]
+    fn budget_completes_most_decks() {
+        let (db, collection) = setup();
+        let expensive = deck(&[(1, 1)]);
+        let cheap = deck(&[(2, 3)]);
+        let cheaper = deck(&[(2, 2)]);
+        let targets = [
+            CraftingTarget {
+                deck: &expensive,
+                priority: 10,
+            },
+            CraftingTarget {
+                deck: &cheap,
+                priority: 1,
+            },
+            CraftingTarget {
+                deck: &cheaper,
+                priority: 1,
+            },
+        ];
+        let plan = plan_crafting(&db, &collection, &targets, 1600).expect("Known cards");
+        assert_eq!(plan.completed, vec![1, 2]);
+        assert_eq!(plan.dust, 1000);
+        assert_eq!(
+            plan.decks,
+            vec![
+                DeckCompletion::OverBudget { dust: 1600 },
+                DeckCompletion::Completed,
+                DeckCompletion::Completed
+            ]
+        );
+
+        let plan = plan_crafting(&db, &collection, &targets, 100).expect("Known cards");
+        assert!(plan.completed.is_empty());
+        assert!(plan.crafts.is_empty());
+    }
+
+    #[test]
+    fn decks_missing_uncraftable_cards_are_not_completed() {
+        let (db, collection) = setup();
+        let blocked = deck(&[(2, 6), (2, 4)]);
+        let craftable = deck(&[(2, 3)]);
+        let targets = [
+            CraftingTarget {
+                deck: &blocked,
+                priority: 5,
+            },
+            CraftingTarget {
+                deck: &craftable,
+                priority: 1,
+            },
+        ];
+        let plan = plan_crafting(&db, &collection, &targets, 10_000).expect("Known cards");
+        assert_eq!(plan.completed, vec![1]);
+        assert_eq!(plan.crafts, vec![(2, 3)]);
+        assert_eq!(
+            plan.decks,
+            vec![
+                DeckCompletion::Uncraftable {
+                    cards: vec![(2, 6)]
+                },
+                DeckCompletion::Completed
+            ]
+        );
+    }
+
+    #[test]
+    fn greedy_combination_fits_budget() {
+        let (db, collection) = setup();
+        let decks: Vec<Deck> = (0..14).map(|_| deck(&[(2, 3)])).collect();
+        let mut targets: Vec<CraftingTarget> = decks
+            .iter()
+            .map(|deck| CraftingTarget { deck, priority: 1 })
+            .collect();
+        let legendary = deck(&[(1, 1)]);
+        targets.push(CraftingTarget {
+            deck: &legendary,
+            priority: 5,
+        });
+        let plan = plan_crafting(&db, &collection, &targets, 200).expect("Known cards");
+        assert_eq!(plan.completed.len(), 14);
+        assert_eq!(plan.crafts, vec![(2, 3)]);
+    }
+}
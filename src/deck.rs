@@ -1,7 +1,15 @@
-use crate::error::DeckCodeError;
+use std::collections::BTreeMap;
+
+use crate::constants::{Class, CURRENT_VERSION};
+use crate::decoder::DecodeLimits;
+use crate::error::{DeckCodeError, DeckSection};
 use crate::format::Format;
+use crate::reader::{
+    capacity, read_card_section, read_copies, read_section_length, read_sideboard_section,
+    read_value, Reader, SliceReader,
+};
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 /// A representation of a Hearthstone deck
 pub struct Deck {
     version: u8,
@@ -16,7 +24,7 @@ pub struct Deck {
     /// The dbfid of the cards in the deck that have a more than two copies. Stored as tupes of (number_of_copies, dbfid). Sorted by dbfid.
     multi_cards: Vec<(u8, u32)>,
     #[allow(clippy::doc_markdown)]
-    /// The dbfid of the cards in the sideboard Stored as tupes of (dbfid, number_of_copies, owner dbfid). Sorted by dbfid.
+    /// The dbfid of the cards in the sideboard Stored as tupes of (dbfid, number_of_copies, owner dbfid). Sorted by dbfid, then owner dbfid.
     sideboard_cards: Vec<(u32, u8, u32)>,
 }
 
@@ -25,10 +33,20 @@ impl Deck {
     #[must_use]
     pub fn total_cards(&self) -> usize {
         let mut card_count = self.single_cards.len() + self.double_cards.len() * 2;
-        card_count += self.multi_cards.iter().fold(0, |acc, t| acc + t.0) as usize;
+        card_count += self
+            .multi_cards
+            .iter()
+            .fold(0, |acc, t| acc + usize::from(t.0));
         card_count
     }
 
+    /// The class of the deck's first hero, or `None` if the hero is not in `constants::HEROES`.
+    /// `constants::HEROES` does not list every hero skin, so prefer `Deck::class_in` when card data is loaded.
+    #[must_use]
+    pub fn class(&self) -> Option<Class> {
+        self.heroes.first().copied().and_then(Class::from_hero)
+    }
+
     /// The number of cards in the deck. Equivalent to the height of the deck when represented in hearthstone.
     fn total_card_slots(&self) -> usize {
         self.single_cards.len() + self.double_cards.len() + self.multi_cards.len()
@@ -62,141 +80,314 @@ impl Deck {
         cards
     }
 
-    /// Create a new deck from vector of u32 bytes.
-    /// This representation is [described by Hearthsim](https://hearthsim.info/docs/deckstrings/).
-    /// # Errors
-    /// Returns an error when the bytes passed cannot produce a functional deck code.
-    #[allow(clippy::too_many_lines)]
-    pub fn new(bytes: &[u32]) -> Result<Self, DeckCodeError> {
-        let total_bytes = bytes.len();
-
-        if total_bytes < 7 {
-            return Err(DeckCodeError::InvalidDeckEncoding {
-                encoding_type: String::from("Length is too small"),
-            });
+    /// The number of copies of each card needed to build the deck, by dbfid. A card in the main deck and in a
+    /// sideboard needs the copies of both.
+    pub(crate) fn needed_copies(&self) -> BTreeMap<u32, u32> {
+        let mut needed = BTreeMap::new();
+        for (count, dbfid, _) in self.cards() {
+            *needed.entry(dbfid).or_insert(0) += u32::from(count);
         }
+        needed
+    }
 
-        if bytes[0] != 0 {
-            return Err(DeckCodeError::InvalidDeckEncoding {
-                encoding_type: String::from("No leading 0 byte found"),
-            });
+    /// The number of copies of a card in the main deck. Returns 0 when the card is not in the deck.
+    #[must_use]
+    pub fn count_of(&self, dbfid: u32) -> u8 {
+        if self.single_cards.binary_search(&dbfid).is_ok() {
+            1
+        } else if self.double_cards.binary_search(&dbfid).is_ok() {
+            2
+        } else {
+            self.multi_cards
+                .binary_search_by_key(&dbfid, |card| card.1)
+                .map_or(0, |index| self.multi_cards[index].0)
         }
+    }
 
-        let version = std::convert::TryInto::<u8>::try_into(bytes[1]).map_err(|_| {
-            DeckCodeError::InvalidDeckEncoding {
-                encoding_type: "Could not read deck code version.".to_owned(),
-            }
-        })?;
+    /// The number of copies of a card in the sideboard of the card `owner`. Returns 0 when the card is not in that sideboard.
+    #[must_use]
+    pub fn sideboard_count_of(&self, dbfid: u32, owner: u32) -> u8 {
+        self.sideboard_cards
+            .binary_search_by_key(&(dbfid, owner), |card| (card.0, card.2))
+            .map_or(0, |index| self.sideboard_cards[index].1)
+    }
 
-        if version != 1 {
-            return Err(DeckCodeError::UnknownVersion {
-                version: u32::from(version),
-            });
-        }
+    /// The cards outside of sideboards as (dbfid, `number_of_copies`), sorted by dbfid.
+    /// Each section is already sorted, so they are merged in linear time.
+    pub(crate) fn main_cards(&self) -> Vec<(u32, u8)> {
+        let singles: Vec<(u32, u8)> = self.single_cards.iter().map(|&card| (card, 1)).collect();
+        let doubles: Vec<(u32, u8)> = self.double_cards.iter().map(|&card| (card, 2)).collect();
+        let multis: Vec<(u32, u8)> = self
+            .multi_cards
+            .iter()
+            .map(|&(count, card)| (card, count))
+            .collect();
+        merge_by_dbfid(&merge_by_dbfid(&singles, &doubles), &multis)
+    }
 
-        let format: Format = Format::from_u32(bytes[2])?;
-
-        // Figure out where each region lies in the bytes
-        let hero_count = bytes[3] as usize;
-        let last_hero_byte: usize = 3 + hero_count;
-        let single_card_count = bytes[last_hero_byte + 1];
-        let last_single_card_byte = next_end(last_hero_byte, single_card_count);
-        if last_single_card_byte > total_bytes {
-            return Err(DeckCodeError::InvalidDeckEncoding {
-                encoding_type: String::from(
-                    "Length of card sections does not match number of bytes",
-                ),
-            });
+    /// The sideboard cards as (dbfid, `number_of_copies`, owner dbfid), sorted by dbfid and then owner.
+    pub(crate) fn sideboard_cards(&self) -> &[(u32, u8, u32)] {
+        &self.sideboard_cards
+    }
+
+    /// Add `count` copies of a card to the deck, moving it to the section matching its new number of copies.
+    /// # Errors
+    /// Returns `DeckCodeError::ZeroCardCount` if `count` is 0 and `DeckCodeError::CardCountOverflow` if the card would have more than 255 copies.
+    pub fn add_card(&mut self, count: u8, dbfid: u32) -> Result<(), DeckCodeError> {
+        if count == 0 {
+            return Err(DeckCodeError::ZeroCardCount { dbfid });
         }
+        let total = self
+            .count_of(dbfid)
+            .checked_add(count)
+            .ok_or(DeckCodeError::CardCountOverflow { dbfid })?;
+        self.set_count(dbfid, total);
+        Ok(())
+    }
 
-        let double_card_count = bytes[last_single_card_byte + 1];
-        let last_double_card_byte = next_end(last_single_card_byte, double_card_count);
-        if last_double_card_byte > total_bytes {
-            return Err(DeckCodeError::InvalidDeckEncoding {
-                encoding_type: String::from(
-                    "Length of card sections does not match number of bytes",
-                ),
-            });
+    /// Remove `count` copies of a card from the deck, moving it to the section matching its new number of copies.
+    /// Removing the last copy of a card also removes its sideboard.
+    /// # Errors
+    /// Returns `DeckCodeError::CardNotInDeck` if the card is not in the deck and `DeckCodeError::NotEnoughCopies` if the deck has fewer than `count` copies.
+    pub fn remove_card(&mut self, count: u8, dbfid: u32) -> Result<(), DeckCodeError> {
+        let available = self.count_of(dbfid);
+        if available == 0 {
+            return Err(DeckCodeError::CardNotInDeck { dbfid });
         }
+        let remaining = available
+            .checked_sub(count)
+            .ok_or(DeckCodeError::NotEnoughCopies {
+                dbfid,
+                requested: count,
+                available,
+            })?;
+        self.set_count(dbfid, remaining);
+        Ok(())
+    }
 
-        let multi_card_count = bytes[last_double_card_byte + 1];
-        let last_multi_card_byte = next_end(last_double_card_byte, multi_card_count * 2); // 2 * because this section stores count and card id
-        if last_multi_card_byte > total_bytes {
-            return Err(DeckCodeError::InvalidDeckEncoding {
-                encoding_type: String::from(
-                    "Length of card sections does not match number of bytes",
-                ),
-            });
+    /// Set the number of copies of a card in the deck, moving it to the section matching `count`.
+    /// A `count` of 0 removes the card and its sideboard from the deck.
+    pub fn set_count(&mut self, dbfid: u32, count: u8) {
+        if let Ok(index) = self.single_cards.binary_search(&dbfid) {
+            self.single_cards.remove(index);
+        } else if let Ok(index) = self.double_cards.binary_search(&dbfid) {
+            self.double_cards.remove(index);
+        } else if let Ok(index) = self.multi_cards.binary_search_by_key(&dbfid, |card| card.1) {
+            self.multi_cards.remove(index);
         }
 
-        // Older deck codes may not have a byte indicating if a sideboard exists. Assume no sideboard if this byte is not present.
-        // +2 because: 1 byte for byte indicating presence of sideboard and then 1 byte for number of cards in sideboard
-        let sideboard_card_count = if last_multi_card_byte + 2 >= total_bytes {
-            0
-        } else {
-            bytes[last_multi_card_byte + 2]
-        };
+        match count {
+            0 => self.sideboard_cards.retain(|card| card.2 != dbfid),
+            1 => insert_sorted(&mut self.single_cards, dbfid),
+            2 => insert_sorted(&mut self.double_cards, dbfid),
+            _ => {
+                let index = self
+                    .multi_cards
+                    .binary_search_by_key(&dbfid, |card| card.1)
+                    .unwrap_or_else(|index| index);
+                self.multi_cards.insert(index, (count, dbfid));
+            }
+        }
+    }
 
-        let last_sideboard_card_byte = next_end(last_multi_card_byte, sideboard_card_count * 2 + 1); // 2 * because this section stores card id, and sideboard_for id. +1 because the first byte indicates presence of a sideboard
+    /// Add `count` copies of a card to the sideboard of the card `owner`.
+    /// # Errors
+    /// Returns `DeckCodeError::ZeroCardCount` if `count` is 0, `DeckCodeError::SideboardOwnerNotInDeck` if `owner` is not in the deck
+    /// and `DeckCodeError::CardCountOverflow` if the sideboard would have more than 255 copies of the card.
+    pub fn add_sideboard_card(
+        &mut self,
+        count: u8,
+        dbfid: u32,
+        owner: u32,
+    ) -> Result<(), DeckCodeError> {
+        if count == 0 {
+            return Err(DeckCodeError::ZeroCardCount { dbfid });
+        }
+        if self.count_of(owner) == 0 {
+            return Err(DeckCodeError::SideboardOwnerNotInDeck { dbfid, owner });
+        }
+        match self
+            .sideboard_cards
+            .binary_search_by_key(&(dbfid, owner), |card| (card.0, card.2))
+        {
+            Ok(index) => {
+                let card = &mut self.sideboard_cards[index];
+                card.1 = card
+                    .1
+                    .checked_add(count)
+                    .ok_or(DeckCodeError::CardCountOverflow { dbfid })?;
+            }
+            Err(index) => self.sideboard_cards.insert(index, (dbfid, count, owner)),
+        }
+        Ok(())
+    }
 
-        if sideboard_card_count > 0 && last_sideboard_card_byte > total_bytes {
-            return Err(DeckCodeError::InvalidDeckEncoding {
-                encoding_type: String::from(
-                    "Length of card sections does not match number of bytes",
-                ),
-            });
+    /// Remove `count` copies of a card from the sideboard of the card `owner`.
+    /// # Errors
+    /// Returns `DeckCodeError::CardNotInDeck` if the card is not in that sideboard and `DeckCodeError::NotEnoughCopies` if the sideboard has fewer than `count` copies.
+    pub fn remove_sideboard_card(
+        &mut self,
+        count: u8,
+        dbfid: u32,
+        owner: u32,
+    ) -> Result<(), DeckCodeError> {
+        let index = self
+            .sideboard_cards
+            .binary_search_by_key(&(dbfid, owner), |card| (card.0, card.2))
+            .map_err(|_| DeckCodeError::CardNotInDeck { dbfid })?;
+        let available = self.sideboard_cards[index].1;
+        match available.checked_sub(count) {
+            Some(0) => {
+                self.sideboard_cards.remove(index);
+            }
+            Some(remaining) => self.sideboard_cards[index].1 = remaining,
+            None => {
+                return Err(DeckCodeError::NotEnoughCopies {
+                    dbfid,
+                    requested: count,
+                    available,
+                })
+            }
         }
+        Ok(())
+    }
 
-        // Iterate over heroes
-        let first_hero_byte: usize = 4;
-        let mut heroes: Vec<u32> = Vec::new();
-        for i in first_hero_byte..=last_hero_byte {
-            heroes.push(bytes[i]);
+    /// Create a deck from cards in any order, placing each card into the section matching its number of copies.
+    ///
+    /// Cards and sideboard cards are expected to have non-zero counts and unique dbfids.
+    pub(crate) fn from_cards(
+        format: Format,
+        mut heroes: Vec<u32>,
+        cards: &[(u8, u32)],
+        mut sideboard_cards: Vec<(u32, u8, u32)>,
+    ) -> Self {
+        let mut single_cards = Vec::new();
+        let mut double_cards = Vec::new();
+        let mut multi_cards = Vec::new();
+        for &(count, card) in cards {
+            match count {
+                1 => single_cards.push(card),
+                2 => double_cards.push(card),
+                _ => multi_cards.push((count, card)),
+            }
         }
 
-        // Iterate over cards
-        let mut single_cards: Vec<u32> = Vec::with_capacity(single_card_count as usize);
-        let first_single_card_byte: usize = last_hero_byte + 2;
-        for i in first_single_card_byte..=last_single_card_byte {
-            single_cards.push(bytes[i]);
+        heroes.sort_unstable();
+        single_cards.sort_unstable();
+        double_cards.sort_unstable();
+        multi_cards.sort_unstable_by_key(|card| card.1);
+        sideboard_cards.sort_unstable_by_key(|card| (card.0, card.2));
+        debug_assert!(
+            sideboard_cards
+                .windows(2)
+                .all(|pair| (pair[0].0, pair[0].2) != (pair[1].0, pair[1].2)),
+            "Sideboard cards must be unique by dbfid and owner"
+        );
+
+        Self {
+            version: CURRENT_VERSION,
+            format,
+            heroes,
+            single_cards,
+            double_cards,
+            multi_cards,
+            sideboard_cards,
         }
+    }
+
+    /// Create a new deck from vector of u32 bytes.
+    /// This representation is [described by Hearthsim](https://hearthsim.info/docs/deckstrings/).
+    /// Sections are bounded by the default `DecodeLimits`.
+    /// # Errors
+    /// Returns an error when the bytes passed cannot produce a functional deck code.
+    pub fn new(bytes: &[u32]) -> Result<Self, DeckCodeError> {
+        Self::read(&mut SliceReader::new(bytes), &DecodeLimits::default())
+    }
 
-        let mut double_cards: Vec<u32> = Vec::with_capacity(double_card_count as usize);
-        let first_double_card_byte: usize = last_single_card_byte + 2;
-        for i in first_double_card_byte..=last_double_card_byte {
-            double_cards.push(bytes[i]);
+    /// Read a deck from a cursor over the integers of a deck code in a single pass.
+    /// This must return `Err` rather than panic on any input.
+    #[deny(
+        clippy::indexing_slicing,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::panic
+    )]
+    pub(crate) fn read<R: Reader>(
+        reader: &mut R,
+        limits: &DecodeLimits,
+    ) -> Result<Self, DeckCodeError> {
+        let header = read_value(reader, DeckSection::Header)?;
+        if header != 0 {
+            return Err(DeckCodeError::MissingHeaderByte { found: header });
         }
 
+        // Version 1 is the only version of the format
+        let version = match read_value(reader, DeckSection::Header)? {
+            1 => 1,
+            version => return Err(DeckCodeError::UnknownVersion { version }),
+        };
+
+        let format: Format = Format::from_u32(read_value(reader, DeckSection::Header)?)?;
+
+        let heroes = read_card_section(reader, DeckSection::Heroes, limits.max_heroes)?;
+        let single_cards = read_card_section(
+            reader,
+            DeckSection::SingleCards,
+            limits.max_cards_per_section,
+        )?;
+        let double_cards = read_card_section(
+            reader,
+            DeckSection::DoubleCards,
+            limits.max_cards_per_section,
+        )?;
+
         // Iterate over card and number pairs
-        let mut multi_cards: Vec<(u8, u32)> = Vec::with_capacity(multi_card_count as usize);
-        let mut index = last_double_card_byte + 2;
-        while index < last_multi_card_byte {
-            let card = bytes[index];
-            let number_of_card =
-                u8::try_from(bytes[index + 1]).map_err(|_| DeckCodeError::InvalidDeckEncoding {
-                    encoding_type: String::from("Amount of single card exceeded 255"),
-                })?;
+        let multi_card_count = read_section_length(
+            reader,
+            DeckSection::MultiCards,
+            limits.max_cards_per_section,
+            0,
+        )?;
+        let mut multi_cards: Vec<(u8, u32)> =
+            Vec::with_capacity(capacity(multi_card_count, reader.remaining() / 2));
+        for _ in 0..multi_card_count {
+            let card = read_value(reader, DeckSection::MultiCards)?;
+            let number_of_card = read_copies(reader, DeckSection::MultiCards)?;
             multi_cards.push((number_of_card, card));
-            index += 2;
         }
 
-        // Iterate over sideboard
-        let mut sideboard_cards: Vec<(u32, u8, u32)> =
-            Vec::with_capacity(sideboard_card_count as usize);
-        if sideboard_card_count > 0 {
-            let mut index = last_multi_card_byte + 3;
-            while index < last_sideboard_card_byte {
-                let card_id = bytes[index];
-                let sideboard_for_card_id = bytes[index + 1];
-                sideboard_cards.push((card_id, 1, sideboard_for_card_id));
-                index += 2;
+        // Older deck codes may not have a byte indicating if a sideboard exists. Assume no sideboard if this byte is not present.
+        let mut sideboard_cards: Vec<(u32, u8, u32)> = Vec::new();
+        let marker_offset = reader.position();
+        match reader.read().map_err(|_| DeckCodeError::InvalidVarint {
+            section: DeckSection::Sideboard,
+            offset: marker_offset,
+        })? {
+            None | Some(0) => {}
+            Some(1) => {
+                // Sideboards mirror the main deck: a 1x section, a 2x section and an Nx section.
+                let limit = limits.max_sideboard_entries;
+                read_sideboard_section(reader, Some(1), limit, &mut sideboard_cards)?;
+                read_sideboard_section(reader, Some(2), limit, &mut sideboard_cards)?;
+                read_sideboard_section(reader, None, limit, &mut sideboard_cards)?;
+            }
+            Some(marker) => {
+                return Err(DeckCodeError::InvalidSideboardMarker {
+                    marker,
+                    offset: marker_offset,
+                });
             }
         }
 
-        single_cards.sort_unstable();
-        double_cards.sort_unstable();
-        multi_cards.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
-        sideboard_cards.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        if reader.remaining() > 0 {
+            return Err(DeckCodeError::TrailingData {
+                offset: reader.position(),
+            });
+        }
+
+        let (single_cards, double_cards, multi_cards) =
+            merge_main_cards(&single_cards, &double_cards, multi_cards)?;
+        let sideboard_cards = merge_sideboard_cards(sideboard_cards)?;
 
         Ok(Self {
             version,
@@ -247,10 +438,6 @@ impl Deck {
             byte_array.push(0);
         } else {
             byte_array.push(1);
-            byte_array.push(
-                u32::try_from(self.sideboard_cards.len())
-                    .expect("More sideboard cards provided than expected"),
-            );
             byte_array.extend(&flatten_sideboard(&self.sideboard_cards));
         }
 
@@ -258,6 +445,97 @@ impl Deck {
     }
 }
 
+/// Merges two lists of (dbfid, `number_of_copies`) sorted by dbfid into one sorted list.
+fn merge_by_dbfid(first: &[(u32, u8)], second: &[(u32, u8)]) -> Vec<(u32, u8)> {
+    let mut merged = Vec::with_capacity(first.len() + second.len());
+    let mut first = first.iter().copied().peekable();
+    let mut second = second.iter().copied().peekable();
+    while let (Some(a), Some(b)) = (first.peek(), second.peek()) {
+        if a.0 <= b.0 {
+            merged.push(*a);
+            first.next();
+        } else {
+            merged.push(*b);
+            second.next();
+        }
+    }
+    merged.extend(first);
+    merged.extend(second);
+    merged
+}
+
+/// The 1x, 2x and Nx sections of the main deck.
+type MainCards = (Vec<u32>, Vec<u32>, Vec<(u8, u32)>);
+
+/// Sum the copies of cards repeated within or across the 1x, 2x and Nx sections, as `DeckBuilder` does, and sort
+/// each card into the section for its total. Without this, `count_of` and `set_count` would only see one of the
+/// entries for a card, and the deck would not encode to the canonical code.
+fn merge_main_cards(
+    single_cards: &[u32],
+    double_cards: &[u32],
+    multi_cards: Vec<(u8, u32)>,
+) -> Result<MainCards, DeckCodeError> {
+    let mut cards: Vec<(u8, u32)> = single_cards
+        .iter()
+        .map(|&dbfid| (1, dbfid))
+        .chain(double_cards.iter().map(|&dbfid| (2, dbfid)))
+        .chain(multi_cards)
+        .collect();
+    cards.sort_by_key(|card| card.1);
+
+    let mut merged: Vec<(u8, u32)> = Vec::with_capacity(cards.len());
+    for (count, dbfid) in cards {
+        match merged.last_mut() {
+            Some(last) if last.1 == dbfid => {
+                last.0 = last
+                    .0
+                    .checked_add(count)
+                    .ok_or(DeckCodeError::CardCountOverflow { dbfid })?;
+            }
+            _ => merged.push((count, dbfid)),
+        }
+    }
+
+    let mut single_cards = Vec::new();
+    let mut double_cards = Vec::new();
+    let mut multi_cards = Vec::new();
+    for (count, dbfid) in merged {
+        match count {
+            1 => single_cards.push(dbfid),
+            2 => double_cards.push(dbfid),
+            _ => multi_cards.push((count, dbfid)),
+        }
+    }
+    Ok((single_cards, double_cards, multi_cards))
+}
+
+/// Sort sideboard cards by dbfid and then owner, summing the copies of repeated entries for the same card and owner
+/// as `DeckBuilder` does. Without this, repeated entries would encode in a different order than they were decoded.
+fn merge_sideboard_cards(
+    mut sideboard_cards: Vec<(u32, u8, u32)>,
+) -> Result<Vec<(u32, u8, u32)>, DeckCodeError> {
+    sideboard_cards.sort_unstable_by_key(|card| (card.0, card.2));
+    let mut merged: Vec<(u32, u8, u32)> = Vec::with_capacity(sideboard_cards.len());
+    for (dbfid, count, owner) in sideboard_cards {
+        match merged.last_mut() {
+            Some(last) if last.0 == dbfid && last.2 == owner => {
+                last.1 = last
+                    .1
+                    .checked_add(count)
+                    .ok_or(DeckCodeError::CardCountOverflow { dbfid })?;
+            }
+            _ => merged.push((dbfid, count, owner)),
+        }
+    }
+    Ok(merged)
+}
+
+fn insert_sorted(cards: &mut Vec<u32>, dbfid: u32) {
+    if let Err(index) = cards.binary_search(&dbfid) {
+        cards.insert(index, dbfid);
+    }
+}
+
 fn flatten_multi_cards(intervals: &[(u8, u32)]) -> Vec<u32> {
     use std::iter::once;
 
@@ -267,20 +545,27 @@ fn flatten_multi_cards(intervals: &[(u8, u32)]) -> Vec<u32> {
         .collect()
 }
 
-fn flatten_sideboard(intervals: &[(u32, u8, u32)]) -> Vec<u32> {
-    use std::iter::once;
+/// Flattens the sideboard into its 1x, 2x and Nx sections, each prefixed with its length.
+fn flatten_sideboard(sideboard_cards: &[(u32, u8, u32)]) -> Vec<u32> {
+    let section_length =
+        |length: usize| u32::try_from(length).expect("More sideboard cards provided than expected");
 
-    intervals
+    let singles: Vec<&(u32, u8, u32)> = sideboard_cards.iter().filter(|c| c.1 == 1).collect();
+    let doubles: Vec<&(u32, u8, u32)> = sideboard_cards.iter().filter(|c| c.1 == 2).collect();
+    // Anything that is not exactly 1 or 2 copies is written with its count so that it survives a round trip
+    let multis: Vec<&(u32, u8, u32)> = sideboard_cards
         .iter()
-        .flat_map(|tup| once(tup.0).chain(once(tup.2)))
-        // Terminating null bytes are only for sideboards
-        .chain(once(0))
-        .chain(once(0))
-        .collect()
-}
+        .filter(|c| c.1 != 1 && c.1 != 2)
+        .collect();
 
-fn next_end(previous_end: usize, total_number: u32) -> usize {
-    previous_end + total_number as usize + 1
+    let mut flattened = Vec::with_capacity(3 + sideboard_cards.len() * 3);
+    flattened.push(section_length(singles.len()));
+    flattened.extend(singles.iter().flat_map(|c| [c.0, c.2]));
+    flattened.push(section_length(doubles.len()));
+    flattened.extend(doubles.iter().flat_map(|c| [c.0, c.2]));
+    flattened.push(section_length(multis.len()));
+    flattened.extend(multis.iter().flat_map(|c| [c.0, u32::from(c.1), c.2]));
+    flattened
 }
 
 #[cfg(test)]
@@ -324,6 +609,70 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn new_reports_missing_header_byte() {
+        let input = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+        assert_eq!(
+            Deck::new(&input),
+            Err(DeckCodeError::MissingHeaderByte { found: 1 })
+        );
+    }
+
+    #[test]
+    fn new_reports_truncated_section_and_offset() {
+        let input = vec![
+            0, 1, 1, // Header
+            1, 7, // 1 Hero
+            7, 1, 2, // A LIE: 7 Single Cards when there are two
+        ];
+        assert_eq!(
+            Deck::new(&input),
+            Err(DeckCodeError::TruncatedSection {
+                section: DeckSection::SingleCards,
+                offset: 8
+            })
+        );
+    }
+
+    #[test]
+    fn new_reports_count_overflow_in_multi_cards() {
+        let input = vec![
+            0, 1, 1, // Header
+            1, 7, // 1 Hero
+            0, 0, // No single or double cards
+            1, 5, 256, // 256 copies of card 5
+        ];
+        assert_eq!(
+            Deck::new(&input),
+            Err(DeckCodeError::CountOverflow {
+                section: DeckSection::MultiCards,
+                offset: 9,
+                count: 256
+            })
+        );
+    }
+
+    #[test]
+    fn new_reports_invalid_sideboard_marker() {
+        let input = vec![0, 1, 1, 1, 7, 0, 0, 0, 2];
+        assert_eq!(
+            Deck::new(&input),
+            Err(DeckCodeError::InvalidSideboardMarker {
+                marker: 2,
+                offset: 8
+            })
+        );
+    }
+
+    #[test]
+    fn new_reports_trailing_data() {
+        let input = vec![0, 1, 1, 1, 7, 0, 0, 0, 0, 5];
+        assert_eq!(
+            Deck::new(&input),
+            Err(DeckCodeError::TrailingData { offset: 9 })
+        );
+    }
+
     #[test]
     fn new_matches_simple_example() {
         let input = vec![
@@ -445,6 +794,132 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn new_reads_all_sideboard_sections() {
+        let input = vec![
+            0, 1, 2, // Standard header
+            1, 274, // 1 Hero: Malfurion
+            1, 102_983, // 1 Single Card: Zilliax Deluxe 3000
+            0,       // 0 Double Cards
+            0,       // 0 Multi Cards
+            1,       // Has sideboard
+            1, 104_951, 102_983, // 1x sideboard section
+            1, 104_948, 102_983, // 2x sideboard section
+            1, 110_440, 3, 102_983, // Nx sideboard section
+        ];
+
+        let result = Deck::new(&input).expect("Decoded correctly");
+        assert_eq!(
+            result.sideboard_cards,
+            vec![
+                (104_948, 2, 102_983),
+                (104_951, 1, 102_983),
+                (110_440, 3, 102_983)
+            ]
+        );
+        assert_eq!(result.to_byte_array(), input);
+    }
+
+    #[test]
+    fn new_returns_err_if_a_sideboard_section_is_truncated() {
+        let input = vec![
+            0, 1, 2, // Standard header
+            1, 274, // 1 Hero: Malfurion
+            1, 102_983, // 1 Single Card: Zilliax Deluxe 3000
+            0,       // 0 Double Cards
+            0,       // 0 Multi Cards
+            1,       // Has sideboard
+            1, 104_951, 102_983, // 1x sideboard section
+            2, 104_948, 102_983, // A LIE: 2 entries in the 2x section when there is one
+        ];
+        let result = Deck::new(&input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn add_card_moves_card_between_sections() {
+        let mut deck = Deck::from_cards(Format::Wild, vec![7], &[(1, 5), (2, 3)], Vec::new());
+
+        deck.add_card(1, 5).expect("Added card");
+        deck.add_card(1, 3).expect("Added card");
+        deck.add_card(1, 4).expect("Added card");
+
+        assert_eq!(deck.single_cards, vec![4]);
+        assert_eq!(deck.double_cards, vec![5]);
+        assert_eq!(deck.multi_cards, vec![(3, 3)]);
+        assert_eq!(
+            deck.to_byte_array(),
+            vec![0, 1, 1, 1, 7, 1, 4, 1, 5, 1, 3, 3, 0]
+        );
+    }
+
+    #[test]
+    fn remove_card_moves_card_between_sections_and_drops_its_sideboard() {
+        let mut deck = Deck::from_cards(
+            Format::Standard,
+            vec![274],
+            &[(3, 1), (2, 2), (1, 3)],
+            vec![(10, 1, 3)],
+        );
+
+        deck.remove_card(1, 1).expect("Removed card");
+        deck.remove_card(1, 2).expect("Removed card");
+        deck.remove_card(1, 3).expect("Removed card");
+
+        assert_eq!(deck.cards(), vec![(1, 2, None), (2, 1, None)]);
+    }
+
+    #[test]
+    fn remove_card_returns_err_for_missing_or_insufficient_copies() {
+        let mut deck = Deck::from_cards(Format::Wild, vec![7], &[(2, 5)], Vec::new());
+
+        assert_eq!(
+            deck.remove_card(1, 6),
+            Err(DeckCodeError::CardNotInDeck { dbfid: 6 })
+        );
+        assert_eq!(
+            deck.remove_card(3, 5),
+            Err(DeckCodeError::NotEnoughCopies {
+                dbfid: 5,
+                requested: 3,
+                available: 2
+            })
+        );
+        assert_eq!(deck.count_of(5), 2);
+    }
+
+    #[test]
+    fn set_count_replaces_number_of_copies() {
+        let mut deck = Deck::from_cards(Format::Wild, vec![7], &[(1, 5)], Vec::new());
+
+        deck.set_count(5, 4);
+        assert_eq!(deck.cards(), vec![(4, 5, None)]);
+
+        deck.set_count(5, 0);
+        assert!(deck.cards().is_empty());
+    }
+
+    #[test]
+    fn add_and_remove_sideboard_cards() {
+        let mut deck = Deck::from_cards(Format::Standard, vec![274], &[(1, 3)], Vec::new());
+
+        assert_eq!(
+            deck.add_sideboard_card(1, 10, 4),
+            Err(DeckCodeError::SideboardOwnerNotInDeck {
+                dbfid: 10,
+                owner: 4
+            })
+        );
+        deck.add_sideboard_card(1, 10, 3).expect("Added card");
+        deck.add_sideboard_card(1, 10, 3).expect("Added card");
+        deck.add_sideboard_card(1, 9, 3).expect("Added card");
+        assert_eq!(deck.sideboard_cards, vec![(9, 1, 3), (10, 2, 3)]);
+
+        deck.remove_sideboard_card(1, 9, 3).expect("Removed card");
+        assert_eq!(deck.sideboard_count_of(9, 3), 0);
+        assert_eq!(deck.sideboard_count_of(10, 3), 2);
+    }
+
     #[test]
     fn total_cards() {
         let input = Deck {
@@ -459,6 +934,84 @@ mod tests {
         assert_eq!(24, input.total_cards());
     }
 
+    #[test]
+    fn main_cards_are_sorted_across_sections() {
+        let deck = Deck::from_cards(
+            Format::Wild,
+            vec![7],
+            &[(2, 1), (1, 4), (3, 2), (1, 3), (2, 5)],
+            vec![(9, 1, 4)],
+        );
+        assert_eq!(
+            deck.main_cards(),
+            vec![(1, 2), (2, 3), (3, 1), (4, 1), (5, 2)]
+        );
+    }
+
+    #[test]
+    fn class_is_class_of_first_hero() {
+        let deck = Deck::from_cards(Format::Standard, vec![637], &[], Vec::new());
+        assert_eq!(deck.class(), Some(Class::Mage));
+
+        let deck = Deck::from_cards(Format::Standard, vec![1], &[], Vec::new());
+        assert_eq!(deck.class(), None);
+    }
+
+    #[test]
+    fn total_cards_counts_more_than_255_copies() {
+        let deck = Deck::from_cards(Format::Wild, vec![7], &[(200, 1), (200, 2)], Vec::new());
+        assert_eq!(deck.total_cards(), 400);
+    }
+
+    #[test]
+    fn needed_copies_sums_main_deck_and_sideboards() {
+        let deck = Deck::from_cards(
+            Format::Wild,
+            vec![7],
+            &[(1, 1), (2, 2)],
+            vec![(1, 1, 2), (3, 1, 2)],
+        );
+        assert_eq!(
+            deck.needed_copies().into_iter().collect::<Vec<_>>(),
+            vec![(1, 2), (2, 2), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn read_merges_repeated_main_deck_entries() {
+        // Card 5 in the 1x and 2x sections, card 6 twice in the 1x section and card 7 in the Nx section with 2 copies
+        let deck =
+            Deck::new(&[0, 1, 1, 1, 7, 3, 5, 6, 6, 1, 5, 1, 7, 2, 0]).expect("Decoded correctly");
+        assert_eq!(deck.count_of(5), 3);
+        assert_eq!(deck.count_of(6), 2);
+        assert_eq!(deck.count_of(7), 2);
+        assert_eq!(deck.total_cards(), 7);
+        assert_eq!(
+            deck,
+            Deck::from_cards(Format::Wild, vec![7], &[(3, 5), (2, 6), (2, 7)], Vec::new())
+        );
+        assert_eq!(Deck::new(&deck.to_byte_array()), Ok(deck.clone()));
+
+        let mut deck = deck;
+        deck.set_count(6, 0);
+        assert_eq!(deck.count_of(6), 0);
+
+        let result = Deck::new(&[0, 1, 1, 1, 7, 1, 5, 0, 1, 5, 255, 0]);
+        assert_eq!(result, Err(DeckCodeError::CardCountOverflow { dbfid: 5 }));
+    }
+
+    #[test]
+    fn read_merges_repeated_sideboard_entries() {
+        // One copy of card 5 in the 2x sideboard section and another in the Nx section, both owned by card 9
+        let deck = Deck::new(&[0, 1, 1, 1, 7, 1, 9, 0, 0, 1, 0, 1, 5, 9, 1, 5, 1, 9])
+            .expect("Decoded correctly");
+        assert_eq!(deck.sideboard_cards(), &[(5, 3, 9)]);
+        assert_eq!(Deck::new(&deck.to_byte_array()), Ok(deck));
+
+        let result = Deck::new(&[0, 1, 1, 1, 7, 1, 9, 0, 0, 1, 0, 1, 5, 9, 1, 5, 254, 9]);
+        assert_eq!(result, Err(DeckCodeError::CardCountOverflow { dbfid: 5 }));
+    }
+
     #[test]
     fn total_card_slots() {
         let input = Deck {
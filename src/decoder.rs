@@ -0,0 +1,203 @@
+use crate::deck::Deck;
+use crate::error::DeckCodeError;
+use crate::normalize::{normalize_into, Normalizations};
+use crate::reader::VarintReader;
+
+use base64::prelude::*;
+
+/// Upper bounds on the size of what the decoder accepts, so that untrusted input cannot cause large allocations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// The maximum length of the base64 deck code, in bytes.
+    pub max_input_length: usize,
+    /// The maximum number of heroes.
+    pub max_heroes: usize,
+    /// The maximum number of entries in each of the single, double and multi card sections.
+    pub max_cards_per_section: usize,
+    /// The maximum number of sideboard entries, across all of the sideboard sections.
+    pub max_sideboard_entries: usize,
+}
+
+impl Default for DecodeLimits {
+    /// Limits that comfortably fit every deck the game can produce.
+    fn default() -> Self {
+        Self {
+            max_input_length: 4096,
+            max_heroes: 16,
+            max_cards_per_section: 128,
+            max_sideboard_entries: 128,
+        }
+    }
+}
+
+/// A reusable deck code decoder.
+///
+/// Decoding walks the base64 decoded bytes once and builds the `Deck` directly from them.
+/// The buffer holding the decoded bytes is kept between calls, so decoding many codes with one `Decoder` does not reallocate it.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buffer: Vec<u8>,
+    normalized: String,
+    limits: DecodeLimits,
+}
+
+impl Decoder {
+    /// Create a decoder using the default `DecodeLimits`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a decoder that rejects deck codes exceeding `limits`.
+    #[must_use]
+    pub fn with_limits(limits: DecodeLimits) -> Self {
+        Self {
+            buffer: Vec::new(),
+            normalized: String::new(),
+            limits,
+        }
+    }
+
+    /// Convert a Hearthstone deck code into a `Deck` struct
+    /// # Errors
+    ///
+    /// Will return `Err` if the version of the deck code is not supported or if
+    /// the deck code is invalid.
+    pub fn decode(&mut self, deck_code: &str) -> Result<Deck, DeckCodeError> {
+        self.check_length(deck_code)?;
+        Self::decode_into(deck_code, &mut self.buffer, &self.limits)
+    }
+
+    /// Convert a pasted Hearthstone deck code into a `Deck` struct, tolerating surrounding and internal whitespace,
+    /// missing `=` padding and the URL-safe base64 alphabet. Returns the deck and the normalizations that were needed.
+    /// # Errors
+    ///
+    /// Will return `Err` if the version of the deck code is not supported or if
+    /// the deck code is invalid once normalized.
+    pub fn decode_lenient(
+        &mut self,
+        deck_code: &str,
+    ) -> Result<(Deck, Normalizations), DeckCodeError> {
+        self.check_length(deck_code)?;
+        let normalizations = normalize_into(deck_code, &mut self.normalized);
+        let deck = Self::decode_into(&self.normalized, &mut self.buffer, &self.limits)?;
+        Ok((deck, normalizations))
+    }
+
+    fn check_length(&self, deck_code: &str) -> Result<(), DeckCodeError> {
+        if deck_code.len() > self.limits.max_input_length {
+            return Err(DeckCodeError::InputTooLong {
+                length: deck_code.len(),
+                limit: self.limits.max_input_length,
+            });
+        }
+        Ok(())
+    }
+
+    fn decode_into(
+        deck_code: &str,
+        buffer: &mut Vec<u8>,
+        limits: &DecodeLimits,
+    ) -> Result<Deck, DeckCodeError> {
+        buffer.clear();
+        BASE64_STANDARD.decode_vec(deck_code, buffer)?;
+        Deck::read(&mut VarintReader::new(buffer), limits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DeckSection;
+
+    #[test]
+    fn decode_reuses_buffer_across_calls() {
+        let mut decoder = Decoder::new();
+        let first = decoder
+            .decode("AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIA")
+            .expect("Decoded correctly");
+        let capacity = decoder.buffer.capacity();
+
+        let second = decoder
+            .decode("AAEBAQcAAAQBAwIDAwMEAw==")
+            .expect("Decoded correctly");
+
+        assert_eq!(first.total_cards(), 30);
+        assert_eq!(second.total_cards(), 12);
+        assert_eq!(decoder.buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn decode_rejects_input_longer_than_limit() {
+        let limits = DecodeLimits {
+            max_input_length: 8,
+            ..DecodeLimits::default()
+        };
+        assert_eq!(
+            Decoder::with_limits(limits).decode("AAEBAQcAAAQBAwIDAwMEAw=="),
+            Err(DeckCodeError::InputTooLong {
+                length: 24,
+                limit: 8
+            })
+        );
+    }
+
+    #[test]
+    fn decode_rejects_sections_larger_than_limit() {
+        let limits = DecodeLimits {
+            max_cards_per_section: 3,
+            ..DecodeLimits::default()
+        };
+        assert_eq!(
+            Decoder::with_limits(limits).decode("AAEBAQcAAAQBAwIDAwMEAw=="),
+            Err(DeckCodeError::SectionTooLarge {
+                section: DeckSection::MultiCards,
+                offset: 7,
+                count: 4,
+                limit: 3
+            })
+        );
+    }
+
+    #[test]
+    fn decode_rejects_huge_hero_count_without_allocating_for_it() {
+        // Header followed by a hero count of u32::MAX
+        let code = BASE64_STANDARD.encode([0, 1, 1, 0xff, 0xff, 0xff, 0xff, 0x0f]);
+        assert_eq!(
+            Decoder::new().decode(&code),
+            Err(DeckCodeError::SectionTooLarge {
+                section: DeckSection::Heroes,
+                offset: 3,
+                count: u32::MAX,
+                limit: 16
+            })
+        );
+    }
+
+    #[test]
+    fn decode_lenient_accepts_pasted_code() {
+        let mut decoder = Decoder::new();
+        let (deck, normalizations) = decoder
+            .decode_lenient(" AAEBAQcAAAQB\nAwIDAwMEAw\n")
+            .expect("Decoded correctly");
+        assert_eq!(Ok(deck), decoder.decode("AAEBAQcAAAQBAwIDAwMEAw=="));
+        assert!(normalizations.trimmed_whitespace);
+        assert!(normalizations.removed_internal_whitespace);
+        assert!(normalizations.fixed_padding);
+        assert!(!normalizations.converted_url_safe_alphabet);
+    }
+
+    #[test]
+    fn decode_is_strict() {
+        let mut decoder = Decoder::new();
+        assert!(decoder.decode(" AAEBAQcAAAQBAwIDAwMEAw==").is_err());
+        assert!(decoder.decode("AAEBAQcAAAQBAwIDAwMEAw").is_err());
+    }
+
+    #[test]
+    fn decode_succeeds_after_a_failed_call() {
+        let mut decoder = Decoder::new();
+        assert!(decoder.decode("not base64!").is_err());
+        assert!(decoder.decode("AAEBAQcAAAQBAwIDAwMEAw==").is_ok());
+    }
+}
@@ -0,0 +1,315 @@
+use std::cmp::Ordering;
+
+use crate::deck::Deck;
+use crate::error::DeckCodeError;
+use crate::export::CardResolver;
+
+/// A card whose number of copies differs between two decks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CardChange {
+    pub dbfid: u32,
+    /// The number of copies in the original deck, 0 if the card was added.
+    pub before: u8,
+    /// The number of copies in the other deck, 0 if the card was removed.
+    pub after: u8,
+}
+
+impl CardChange {
+    /// The number of copies added, negative when copies were removed.
+    #[must_use]
+    pub fn delta(&self) -> i16 {
+        i16::from(self.after) - i16::from(self.before)
+    }
+}
+
+/// The changes to the sideboard of one card.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SideboardChanges {
+    /// The dbfid of the card the sideboard belongs to.
+    pub owner: u32,
+    /// Sorted by dbfid.
+    pub changes: Vec<CardChange>,
+}
+
+/// The differences between two decks, as returned by `Deck::diff`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeckDiff {
+    /// Cards added, removed or with a changed number of copies, outside of sideboards. Sorted by dbfid.
+    pub cards: Vec<CardChange>,
+    /// Sideboard changes grouped by the card owning the sideboard. Sorted by owner dbfid.
+    pub sideboards: Vec<SideboardChanges>,
+    pub heroes_changed: bool,
+    pub format_changed: bool,
+}
+
+impl DeckDiff {
+    /// Returns true when the two decks are identical.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The cards that are only in the other deck.
+    pub fn added(&self) -> impl Iterator<Item = &CardChange> {
+        self.cards.iter().filter(|change| change.before == 0)
+    }
+
+    /// The cards that are only in the original deck.
+    pub fn removed(&self) -> impl Iterator<Item = &CardChange> {
+        self.cards.iter().filter(|change| change.after == 0)
+    }
+
+    /// Write each change to the cards as a line such as `+1 Fireball` or `-2 Frostbolt`.
+    /// Sideboard changes follow, with the name of the owning card in brackets, e.g. `+1 Virus Module (Zilliax Deluxe 3000)`.
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::UnknownCard` if the resolver does not know a card.
+    pub fn format_lines<R: CardResolver>(
+        &self,
+        resolver: &R,
+    ) -> Result<Vec<String>, DeckCodeError> {
+        let name = |dbfid: u32| {
+            resolver
+                .card(dbfid)
+                .map(|(name, _)| name)
+                .ok_or(DeckCodeError::UnknownCard { dbfid })
+        };
+
+        let mut lines = Vec::new();
+        for change in &self.cards {
+            lines.push(format!("{:+} {}", change.delta(), name(change.dbfid)?));
+        }
+        for sideboard in &self.sideboards {
+            let owner = name(sideboard.owner)?;
+            for change in &sideboard.changes {
+                lines.push(format!(
+                    "{:+} {} ({owner})",
+                    change.delta(),
+                    name(change.dbfid)?
+                ));
+            }
+        }
+        Ok(lines)
+    }
+}
+
+impl Deck {
+    /// The changes needed to turn this deck into `other`.
+    ///
+    /// Cards are kept sorted by dbfid, so the two decks are compared in a single pass over each.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> DeckDiff {
+        let cards = diff_counts(&self.main_cards(), &other.main_cards())
+            .into_iter()
+            .map(|(dbfid, before, after)| CardChange {
+                dbfid,
+                before,
+                after,
+            })
+            .collect();
+
+        let by_owner = |deck: &Self| {
+            let mut cards: Vec<((u32, u32), u8)> = deck
+                .sideboard_cards()
+                .iter()
+                .map(|&(dbfid, count, owner)| ((owner, dbfid), count))
+                .collect();
+            cards.sort_unstable_by_key(|card| card.0);
+            cards
+        };
+        let mut sideboards: Vec<SideboardChanges> = Vec::new();
+        for ((owner, dbfid), before, after) in diff_counts(&by_owner(self), &by_owner(other)) {
+            let change = CardChange {
+                dbfid,
+                before,
+                after,
+            };
+            match sideboards.last_mut() {
+                Some(sideboard) if sideboard.owner == owner => sideboard.changes.push(change),
+                _ => sideboards.push(SideboardChanges {
+                    owner,
+                    changes: vec![change],
+                }),
+            }
+        }
+
+        DeckDiff {
+            cards,
+            sideboards,
+            heroes_changed: self.heroes != other.heroes,
+            format_changed: self.format != other.format,
+        }
+    }
+}
+
+/// Merges two lists of (key, count) sorted by key, returning (key, before, after) for every key whose count differs.
+fn diff_counts<K: Ord + Copy>(before: &[(K, u8)], after: &[(K, u8)]) -> Vec<(K, u8, u8)> {
+    let mut changes = Vec::new();
+    let mut before = before.iter().copied().peekable();
+    let mut after = after.iter().copied().peekable();
+    loop {
+        let (key, old, new) = match (before.peek(), after.peek()) {
+            (Some(&(old_key, old)), Some(&(new_key, new))) => match old_key.cmp(&new_key) {
+                Ordering::Less => {
+                    before.next();
+                    (old_key, old, 0)
+                }
+                Ordering::Greater => {
+                    after.next();
+                    (new_key, 0, new)
+                }
+                Ordering::Equal => {
+                    before.next();
+                    after.next();
+                    (old_key, old, new)
+                }
+            },
+            (Some(&(key, old)), None) => {
+                before.next();
+                (key, old, 0)
+            }
+            (None, Some(&(key, new))) => {
+                after.next();
+                (key, 0, new)
+            }
+            (None, None) => return changes,
+        };
+        if old != new {
+            changes.push((key, old, new));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    struct TestResolver;
+
+    impl CardResolver for TestResolver {
+        fn card(&self, dbfid: u32) -> Option<(String, u32)> {
+            let name = match dbfid {
+                315 => "Fireball",
+                662 => "Frostbolt",
+                192 => "Ice Block",
+                4 => "Zilliax Deluxe 3000",
+                5 => "Virus Module",
+                6 => "Perfect Module",
+                _ => return None,
+            };
+            Some((name.to_owned(), 0))
+        }
+
+        fn hero_class(&self, _: u32) -> Option<String> {
+            None
+        }
+    }
+
+    fn change(dbfid: u32, before: u8, after: u8) -> CardChange {
+        CardChange {
+            dbfid,
+            before,
+            after,
+        }
+    }
+
+    #[test]
+    fn diff_of_identical_decks_is_empty() {
+        let deck = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(2, 315), (1, 192)],
+            Vec::new(),
+        );
+        assert!(deck.diff(&deck).is_empty());
+    }
+
+    #[test]
+    fn diff_lists_added_removed_and_changed_cards() {
+        let before = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(2, 662), (1, 192), (3, 4)],
+            Vec::new(),
+        );
+        let after = Deck::from_cards(
+            Format::Wild,
+            vec![637],
+            &[(1, 315), (2, 192), (3, 4)],
+            Vec::new(),
+        );
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.cards,
+            vec![change(192, 1, 2), change(315, 0, 1), change(662, 2, 0)]
+        );
+        assert_eq!(diff.added().collect::<Vec<_>>(), vec![&change(315, 0, 1)]);
+        assert_eq!(diff.removed().collect::<Vec<_>>(), vec![&change(662, 2, 0)]);
+        assert!(diff.format_changed);
+        assert!(!diff.heroes_changed);
+    }
+
+    #[test]
+    fn diff_groups_sideboard_changes_by_owner() {
+        let before = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(1, 4), (1, 192)],
+            vec![(5, 1, 4), (6, 1, 4)],
+        );
+        let after = Deck::from_cards(
+            Format::Standard,
+            vec![930],
+            &[(1, 4), (1, 192)],
+            vec![(6, 1, 4), (5, 1, 192)],
+        );
+        let diff = before.diff(&after);
+        assert!(diff.cards.is_empty());
+        assert!(diff.heroes_changed);
+        assert_eq!(
+            diff.sideboards,
+            vec![
+                SideboardChanges {
+                    owner: 4,
+                    changes: vec![change(5, 1, 0)]
+                },
+                SideboardChanges {
+                    owner: 192,
+                    changes: vec![change(5, 0, 1)]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn format_lines_writes_signed_counts_and_names() {
+        let before = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(2, 662), (1, 4)],
+            vec![(5, 1, 4)],
+        );
+        let after = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(1, 315), (1, 4)],
+            vec![(6, 1, 4)],
+        );
+        assert_eq!(
+            before.diff(&after).format_lines(&TestResolver),
+            Ok(vec![
+                "+1 Fireball".to_owned(),
+                "-2 Frostbolt".to_owned(),
+                "-1 Virus Module (Zilliax Deluxe 3000)".to_owned(),
+                "+1 Perfect Module (Zilliax Deluxe 3000)".to_owned(),
+            ])
+        );
+
+        let after = Deck::from_cards(Format::Standard, vec![637], &[(1, 9)], Vec::new());
+        assert_eq!(
+            before.diff(&after).format_lines(&TestResolver),
+            Err(DeckCodeError::UnknownCard { dbfid: 9 })
+        );
+    }
+}
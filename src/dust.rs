@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use crate::cards::{Card, CardDb, Rarity};
+use crate::deck::Deck;
+use crate::error::DeckCodeError;
+
+/// Sets whose cards are given to every player and cannot be crafted.
+const UNCRAFTABLE_SETS: &[&str] = &["CORE"];
+
+/// Looks up how many copies of a card a player owns.
+pub trait OwnedCards {
+    /// The number of copies of the card with the given dbfid the player owns, in any finish.
+    fn owned_copies(&self, dbfid: u32) -> u32;
+}
+
+impl<S: BuildHasher> OwnedCards for HashMap<u32, u32, S> {
+    fn owned_copies(&self, dbfid: u32) -> u32 {
+        self.get(&dbfid).copied().unwrap_or(0)
+    }
+}
+
+/// The dust needed to craft a deck, broken down by rarity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DustCost {
+    pub common: u32,
+    pub rare: u32,
+    pub epic: u32,
+    pub legendary: u32,
+}
+
+impl DustCost {
+    /// The dust needed for the whole deck.
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        self.common + self.rare + self.epic + self.legendary
+    }
+
+    fn add(&mut self, rarity: Rarity, copies: u32) {
+        let dust = crafting_cost(rarity) * copies;
+        match rarity {
+            Rarity::Common => self.common += dust,
+            Rarity::Rare => self.rare += dust,
+            Rarity::Epic => self.epic += dust,
+            Rarity::Legendary => self.legendary += dust,
+            Rarity::Free | Rarity::Unknown => {}
+        }
+    }
+}
+
+/// The dust needed to craft one copy of a card of the given rarity.
+#[must_use]
+pub fn crafting_cost(rarity: Rarity) -> u32 {
+    match rarity {
+        Rarity::Common => 40,
+        Rarity::Rare => 100,
+        Rarity::Epic => 400,
+        Rarity::Legendary => 1600,
+        Rarity::Free | Rarity::Unknown => 0,
+    }
+}
+
+/// Returns true if the card can be crafted with dust.
+/// Uncollectible cards, free cards and cards from the Core set cannot.
+#[must_use]
+pub fn is_craftable(card: &Card) -> bool {
+    card.collectible
+        && card.rarity.is_some_and(|rarity| crafting_cost(rarity) > 0)
+        && !UNCRAFTABLE_SETS.contains(&card.set.as_str())
+}
+
+/// Returns true if every player owns the card without crafting it, as for free cards and cards from the Core set.
+pub(crate) fn is_given_to_every_player(card: &Card) -> bool {
+    card.collectible
+        && (card.rarity == Some(Rarity::Free) || UNCRAFTABLE_SETS.contains(&card.set.as_str()))
+}
+
+impl Deck {
+    /// The dust needed to craft every card in the deck, including sideboards.
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::UnknownCard` if a card is not in the database.
+    pub fn dust_cost(&self, db: &CardDb) -> Result<DustCost, DeckCodeError> {
+        self.missing_dust_cost(db, &HashMap::<u32, u32>::new())
+    }
+
+    /// The dust needed to craft the cards in the deck, including sideboards, that are not in `owned`.
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::UnknownCard` if a card is not in the database.
+    pub fn missing_dust_cost<C: OwnedCards>(
+        &self,
+        db: &CardDb,
+        owned: &C,
+    ) -> Result<DustCost, DeckCodeError> {
+        let mut cost = DustCost::default();
+        for (dbfid, copies) in self.needed_copies() {
+            let card = db.get(dbfid).ok_or(DeckCodeError::UnknownCard { dbfid })?;
+            if let Some(rarity) = card.rarity.filter(|_| is_craftable(card)) {
+                cost.add(rarity, copies.saturating_sub(owned.owned_copies(dbfid)));
+            }
+        }
+        Ok(cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    const CARDS: &str = r#"[
+        {"cardClass":"MAGE","collectible":true,"cost":4,"dbfId":315,"id":"CS2_029","name":"Fireball","rarity":"COMMON","set":"CORE","type":"SPELL"},
+        {"cardClass":"MAGE","collectible":true,"cost":3,"dbfId":192,"id":"EX1_295","name":"Ice Block","rarity":"EPIC","set":"EXPERT1","type":"SPELL"},
+        {"cardClass":"NEUTRAL","collectible":true,"cost":7,"dbfId":1,"id":"EX1_298","name":"Ragnaros the Firelord","rarity":"LEGENDARY","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"MAGE","collectible":true,"cost":1,"dbfId":2,"id":"EX1_277","name":"Arcane Missiles","rarity":"FREE","set":"EXPERT1","type":"SPELL"},
+        {"cardClass":"MAGE","collectible":true,"cost":2,"dbfId":3,"id":"EX1_012","name":"Bloodmage Thalnos","rarity":"RARE","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"NEUTRAL","collectible":true,"cost":3,"dbfId":4,"id":"EX1_007","name":"Acolyte of Pain","rarity":"COMMON","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"NEUTRAL","collectible":false,"cost":4,"dbfId":5,"id":"TOY_330t5","name":"Virus Module","rarity":"LEGENDARY","set":"WHIZBANGS_WORKSHOP","type":"MINION"}
+    ]"#;
+
+    fn deck() -> Deck {
+        Deck::from_cards(
+            Format::Wild,
+            vec![637],
+            &[(2, 315), (2, 192), (1, 1), (2, 2), (1, 3), (2, 4)],
+            vec![(5, 1, 1), (4, 1, 1)],
+        )
+    }
+
+    #[test]
+    fn dust_cost_is_broken_down_by_rarity() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let cost = deck().dust_cost(&db).expect("Known cards");
+        assert_eq!(
+            cost,
+            DustCost {
+                common: 120,
+                rare: 100,
+                epic: 800,
+                legendary: 1600,
+            }
+        );
+        assert_eq!(cost.total(), 2620);
+    }
+
+    #[test]
+    fn missing_dust_cost_skips_owned_copies() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let owned: HashMap<u32, u32> = [(192, 1), (1, 3), (4, 2)].into_iter().collect();
+        let cost = deck().missing_dust_cost(&db, &owned).expect("Known cards");
+        assert_eq!(
+            cost,
+            DustCost {
+                common: 40,
+                rare: 100,
+                epic: 400,
+                legendary: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn dust_cost_returns_err_for_unknown_card() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let deck = Deck::from_cards(Format::Wild, vec![637], &[(1, 99)], Vec::new());
+        assert_eq!(
+            deck.dust_cost(&db),
+            Err(DeckCodeError::UnknownCard { dbfid: 99 })
+        );
+    }
+}
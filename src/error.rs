@@ -1,16 +1,140 @@
+use std::fmt;
+
 use base64::DecodeError;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// The sections of a deck code, in the order they are encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeckSection {
+    /// The leading 0 byte, version and format
+    Header,
+    Heroes,
+    SingleCards,
+    DoubleCards,
+    MultiCards,
+    Sideboard,
+}
+
+impl fmt::Display for DeckSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Header => "header",
+            Self::Heroes => "heroes",
+            Self::SingleCards => "single cards",
+            Self::DoubleCards => "double cards",
+            Self::MultiCards => "multi cards",
+            Self::Sideboard => "sideboard",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Decoding errors report an `offset` into the decoded input.
+/// This is a byte offset into the base64 decoded bytes when decoding a deck code, and an index into the slice for `Deck::new`.
 #[derive(Error, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DeckCodeError {
-    #[error("Invalid deck encoding: {encoding_type:?}.")]
-    InvalidDeckEncoding { encoding_type: String },
     #[error("Invalid input code.")]
-    InvalidBase64(#[from] DecodeError),
+    InvalidBase64(
+        #[from]
+        #[cfg_attr(feature = "serde", serde(with = "DecodeErrorDef"))]
+        DecodeError,
+    ),
+    #[error("Expected a leading 0 byte but found {found}.")]
+    MissingHeaderByte { found: u32 },
+    #[error("The {section} section ends early at offset {offset}.")]
+    TruncatedSection { section: DeckSection, offset: usize },
+    #[error("Invalid varint in the {section} section at offset {offset}.")]
+    InvalidVarint { section: DeckSection, offset: usize },
+    #[error(
+        "A card in the {section} section at offset {offset} has {count} copies, more than 255."
+    )]
+    CountOverflow {
+        section: DeckSection,
+        offset: usize,
+        count: u32,
+    },
+    #[error("Expected a sideboard marker of 0 or 1 at offset {offset} but found {marker}.")]
+    InvalidSideboardMarker { marker: u32, offset: usize },
+    #[error("The {section} section at offset {offset} has {count} entries, more than the limit of {limit}.")]
+    SectionTooLarge {
+        section: DeckSection,
+        offset: usize,
+        count: u32,
+        limit: usize,
+    },
+    #[error("The deck code is {length} bytes long, more than the limit of {limit}.")]
+    InputTooLong { length: usize, limit: usize },
+    #[error("Unexpected data after the end of the deck at offset {offset}.")]
+    TrailingData { offset: usize },
+    #[error("Card {dbfid} could not be resolved.")]
+    UnknownCard { dbfid: u32 },
+    #[error("No deck code found in the text.")]
+    MissingDeckCode,
     #[error("Unknown deck format: {deck_format}.")]
     UnknownDeckFormat { deck_format: u32 },
     #[error("Unknown deck code version: {version}.")]
     UnknownVersion { version: u32 },
+    #[error("Card {dbfid} must have at least one copy.")]
+    ZeroCardCount { dbfid: u32 },
+    #[error("Card {dbfid} has more than 255 copies.")]
+    CardCountOverflow { dbfid: u32 },
+    #[error("Sideboard card {dbfid} belongs to card {owner}, which is not in the deck.")]
+    SideboardOwnerNotInDeck { dbfid: u32, owner: u32 },
+    #[error("Card {dbfid} is not in the deck.")]
+    CardNotInDeck { dbfid: u32 },
+    #[error("Cannot remove {requested} copies of card {dbfid} when the deck has {available}.")]
+    NotEnoughCopies {
+        dbfid: u32,
+        requested: u8,
+        available: u8,
+    },
+    #[error("Invalid patch operation: {operation}.")]
+    InvalidPatch { operation: String },
     #[error("Unknown error processing deck code")]
     Unknown,
+    /// No longer returned. Decoding reports the section and offset of a failure through the variants above.
+    #[deprecated(
+        since = "0.4.0",
+        note = "decoding now returns a dedicated variant such as `TruncatedSection`"
+    )]
+    #[error("Invalid deck encoding: {encoding_type:?}.")]
+    InvalidDeckEncoding { encoding_type: String },
+}
+
+/// Mirrors `base64::DecodeError`, which does not implement serde's traits.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "DecodeError")]
+#[allow(clippy::enum_variant_names)]
+enum DecodeErrorDef {
+    InvalidByte(usize, u8),
+    InvalidLength(usize),
+    InvalidLastSymbol(usize, u8),
+    InvalidPadding,
+}
+
+/// Errors loading a card database.
+#[cfg(feature = "cards")]
+#[derive(Error, Debug)]
+pub enum CardDbError {
+    #[error("Could not read card data: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid card data: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Errors loading a player's collection.
+#[derive(Error, Debug)]
+pub enum CollectionError {
+    #[error("Could not read collection: {0}")]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "cards")]
+    #[error("Invalid collection: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Line {line} of the collection is not a card entry.")]
+    InvalidCsvLine { line: usize },
 }
@@ -0,0 +1,317 @@
+use std::fmt::Write;
+
+use crate::deck::Deck;
+use crate::decoder::Decoder;
+use crate::encode_deck_code;
+use crate::error::DeckCodeError;
+
+/// The comment the Hearthstone client writes after the deck code.
+const EXPORT_FOOTER: &str =
+    "# To use this deck, copy it to your clipboard and create a new deck in Hearthstone";
+
+/// Looks up the card data needed to write an export block.
+pub trait CardResolver {
+    /// The name and mana cost of the card with the given dbfid, or `None` if it is not known.
+    fn card(&self, dbfid: u32) -> Option<(String, u32)>;
+
+    /// The class of the hero with the given dbfid as shown on the `# Class:` line, or `None` if it is not known.
+    fn hero_class(&self, dbfid: u32) -> Option<String>;
+}
+
+/// A card as listed in the comments of a Hearthstone export block, e.g. `# 2x (0) Backstab`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListedCard {
+    pub count: u8,
+    pub cost: u32,
+    pub name: String,
+    /// The name of the card whose sideboard this card is listed under, if any.
+    pub sideboard_owner: Option<String>,
+}
+
+/// A deck copied from the Hearthstone client, including the comments surrounding its deck code.
+#[derive(Debug, PartialEq)]
+pub struct DeckExport {
+    /// The deck name from the `### Name` line.
+    pub name: Option<String>,
+    /// The class from the `# Class:` line.
+    pub class: Option<String>,
+    /// The format from the `# Format:` line, as written by the client.
+    pub format_label: Option<String>,
+    /// The cards listed in the comments, in the order they were listed.
+    pub listed_cards: Vec<ListedCard>,
+    pub deck: Deck,
+}
+
+impl DeckExport {
+    /// Parse the text the Hearthstone client copies to the clipboard when exporting a deck.
+    ///
+    /// The deck code is the first line that is not a `#` comment and decodes successfully.
+    /// Comment lines that are not understood are ignored.
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::MissingDeckCode` if the text has no deck code line,
+    /// or the error from decoding the first deck code line if none of them decode.
+    pub fn parse(text: &str) -> Result<Self, DeckCodeError> {
+        let mut name = None;
+        let mut class = None;
+        let mut format_label = None;
+        let mut listed_cards: Vec<ListedCard> = Vec::new();
+        let mut deck = None;
+        let mut first_error = None;
+        let mut decoder = Decoder::new();
+
+        for line in text.lines().map(str::trim_end) {
+            if let Some(deck_name) = line.strip_prefix("###") {
+                name = Some(deck_name.trim().to_owned());
+            } else if let Some(comment) = line.strip_prefix('#') {
+                let is_sideboard = comment.starts_with("  ");
+                let comment = comment.trim();
+                if let Some(value) = comment.strip_prefix("Class:") {
+                    class = Some(value.trim().to_owned());
+                } else if let Some(value) = comment.strip_prefix("Format:") {
+                    format_label = Some(value.trim().to_owned());
+                } else if let Some((count, cost, card_name)) = parse_card_line(comment) {
+                    let sideboard_owner = if is_sideboard {
+                        listed_cards
+                            .iter()
+                            .rev()
+                            .find(|card| card.sideboard_owner.is_none())
+                            .map(|owner| owner.name.clone())
+                    } else {
+                        None
+                    };
+                    listed_cards.push(ListedCard {
+                        count,
+                        cost,
+                        name: card_name.to_owned(),
+                        sideboard_owner,
+                    });
+                }
+            } else if !line.trim().is_empty() && deck.is_none() {
+                match decoder.decode_lenient(line) {
+                    Ok((found, _)) => deck = Some(found),
+                    Err(error) => {
+                        first_error.get_or_insert(error);
+                    }
+                }
+            }
+        }
+
+        let deck = deck.ok_or_else(|| first_error.unwrap_or(DeckCodeError::MissingDeckCode))?;
+        Ok(Self {
+            name,
+            class,
+            format_label,
+            listed_cards,
+            deck,
+        })
+    }
+}
+
+/// Write a deck in the layout the Hearthstone client uses when exporting a deck, so that it can be pasted back into the game.
+///
+/// Cards are listed by cost and then name, with sideboard cards indented under the card they belong to.
+/// # Errors
+///
+/// Returns `DeckCodeError::UnknownCard` if the resolver does not know a card or the class of a hero.
+pub fn format_export<R: CardResolver>(
+    deck: &Deck,
+    name: &str,
+    resolver: &R,
+) -> Result<String, DeckCodeError> {
+    let resolve = |dbfid: u32| {
+        resolver
+            .card(dbfid)
+            .ok_or(DeckCodeError::UnknownCard { dbfid })
+    };
+
+    let mut main_cards = Vec::new();
+    let mut sideboard_cards = Vec::new();
+    for (count, dbfid, owner) in deck.cards() {
+        let (card_name, cost) = resolve(dbfid)?;
+        match owner {
+            Some(owner) => sideboard_cards.push((owner, cost, card_name, count)),
+            None => main_cards.push((dbfid, cost, card_name, count)),
+        }
+    }
+    main_cards.sort_by(|a, b| (a.1, &a.2).cmp(&(b.1, &b.2)));
+    sideboard_cards.sort_by(|a, b| (a.1, &a.2).cmp(&(b.1, &b.2)));
+
+    let mut text = String::new();
+    // Writing to a String cannot fail
+    let _ = writeln!(text, "### {name}");
+    if let Some(&hero) = deck.heroes.first() {
+        let class = resolver
+            .hero_class(hero)
+            .ok_or(DeckCodeError::UnknownCard { dbfid: hero })?;
+        let _ = writeln!(text, "# Class: {class}");
+    }
+    let _ = writeln!(text, "# Format: {}", deck.format);
+    text.push_str("#\n");
+    for (dbfid, cost, card_name, count) in &main_cards {
+        let _ = writeln!(text, "# {count}x ({cost}) {card_name}");
+        for (_, cost, card_name, count) in sideboard_cards.iter().filter(|card| card.0 == *dbfid) {
+            let _ = writeln!(text, "#   {count}x ({cost}) {card_name}");
+        }
+    }
+    text.push_str("#\n");
+    text.push_str(&encode_deck_code(deck));
+    text.push_str("\n#\n");
+    text.push_str(EXPORT_FOOTER);
+    text.push('\n');
+    Ok(text)
+}
+
+/// Parses `2x (0) Backstab` into its count, cost and name.
+fn parse_card_line(line: &str) -> Option<(u8, u32, &str)> {
+    let (count, rest) = line.split_once("x (")?;
+    let (cost, name) = rest.split_once(") ")?;
+    Some((count.parse().ok()?, cost.parse().ok()?, name.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    const DUNGAR_DRUID: &str = "### Dungar Druid
+# Class: Druid
+# Format: Standard
+#
+# 2x (0) Innervate
+# 1x (9) Zilliax Deluxe 3000
+#   1x (0) Zilliax Deluxe 3000
+#   1x (4) Virus Module
+#   1x (5) Perfect Module
+# 1x (10) Eonar, the Life-Binder
+#
+AAECAZICBp/zBamVBvajBsekBtrBBoviBgyunwSaoAagoAaHqAbvqQbDugbQygbzygaL3Aad4wb75Qad6wYAAQP0swbHpAb3swbHpAbo3gbHpAYAAA==
+#
+# To use this deck, copy it to your clipboard and create a new deck in Hearthstone
+";
+
+    fn listed(count: u8, cost: u32, name: &str, owner: Option<&str>) -> ListedCard {
+        ListedCard {
+            count,
+            cost,
+            name: name.to_owned(),
+            sideboard_owner: owner.map(str::to_owned),
+        }
+    }
+
+    struct TestResolver;
+
+    impl CardResolver for TestResolver {
+        fn card(&self, dbfid: u32) -> Option<(String, u32)> {
+            let (name, cost) = match dbfid {
+                1 => ("Backstab", 0),
+                2 => ("Preparation", 0),
+                3 => ("Cold Blood", 1),
+                4 => ("Zilliax Deluxe 3000", 9),
+                5 => ("Virus Module", 4),
+                6 => ("Perfect Module", 5),
+                _ => return None,
+            };
+            Some((name.to_owned(), cost))
+        }
+
+        fn hero_class(&self, dbfid: u32) -> Option<String> {
+            (dbfid == 930).then(|| "Rogue".to_owned())
+        }
+    }
+
+    #[test]
+    fn format_export_matches_client_layout() {
+        let deck = Deck::from_cards(
+            Format::Standard,
+            vec![930],
+            &[(2, 2), (1, 3), (2, 1), (1, 4)],
+            vec![(6, 1, 4), (5, 1, 4)],
+        );
+        let text = format_export(&deck, "Tempo Rogue", &TestResolver).expect("Formatted");
+        let expected = format!(
+            "### Tempo Rogue
+# Class: Rogue
+# Format: Standard
+#
+# 2x (0) Backstab
+# 2x (0) Preparation
+# 1x (1) Cold Blood
+# 1x (9) Zilliax Deluxe 3000
+#   1x (4) Virus Module
+#   1x (5) Perfect Module
+#
+{}
+#
+# To use this deck, copy it to your clipboard and create a new deck in Hearthstone
+",
+            encode_deck_code(&deck)
+        );
+        assert_eq!(text, expected);
+
+        let parsed = DeckExport::parse(&text).expect("Parsed correctly");
+        assert_eq!(parsed.deck, deck);
+        assert_eq!(parsed.listed_cards.len(), 6);
+    }
+
+    #[test]
+    fn format_export_returns_err_for_unknown_card() {
+        let deck = Deck::from_cards(Format::Standard, vec![930], &[(1, 99)], Vec::new());
+        assert_eq!(
+            format_export(&deck, "Rogue", &TestResolver),
+            Err(DeckCodeError::UnknownCard { dbfid: 99 })
+        );
+    }
+
+    #[test]
+    fn parse_reads_header_cards_and_deck() {
+        let export = DeckExport::parse(DUNGAR_DRUID).expect("Parsed correctly");
+        assert_eq!(export.name.as_deref(), Some("Dungar Druid"));
+        assert_eq!(export.class.as_deref(), Some("Druid"));
+        assert_eq!(export.format_label.as_deref(), Some("Standard"));
+        assert_eq!(export.deck.format, Format::Standard);
+        assert_eq!(export.deck.heroes, vec![274]);
+        assert_eq!(
+            export.listed_cards,
+            vec![
+                listed(2, 0, "Innervate", None),
+                listed(1, 9, "Zilliax Deluxe 3000", None),
+                listed(1, 0, "Zilliax Deluxe 3000", Some("Zilliax Deluxe 3000")),
+                listed(1, 4, "Virus Module", Some("Zilliax Deluxe 3000")),
+                listed(1, 5, "Perfect Module", Some("Zilliax Deluxe 3000")),
+                listed(1, 10, "Eonar, the Life-Binder", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_accepts_windows_line_endings() {
+        let text = DUNGAR_DRUID.replace('\n', "\r\n");
+        let export = DeckExport::parse(&text).expect("Parsed correctly");
+        assert_eq!(export.listed_cards.len(), 6);
+    }
+
+    #[test]
+    fn parse_accepts_bare_deck_code() {
+        let export = DeckExport::parse("AAEBAQcAAAQBAwIDAwMEAw==").expect("Parsed correctly");
+        assert_eq!(export.name, None);
+        assert!(export.listed_cards.is_empty());
+        assert_eq!(export.deck.total_cards(), 12);
+    }
+
+    #[test]
+    fn parse_returns_err_without_deck_code() {
+        assert_eq!(
+            DeckExport::parse("### Rogue\n# Class: Rogue\n"),
+            Err(DeckCodeError::MissingDeckCode)
+        );
+    }
+
+    #[test]
+    fn parse_returns_decode_error_for_invalid_deck_code() {
+        assert!(matches!(
+            DeckExport::parse("### Rogue\nnot a deck code!\n"),
+            Err(DeckCodeError::InvalidBase64(_))
+        ));
+    }
+}
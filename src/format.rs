@@ -1,6 +1,14 @@
+use std::fmt;
+
+#[cfg(feature = "cards")]
+use crate::cards::Card;
 use crate::error::DeckCodeError;
+#[cfg(feature = "cards")]
+use crate::rotation::{legal_sets, Date, LegalSets};
 
-#[derive(PartialEq, PartialOrd, Debug)]
+/// Serialized by name, e.g. `"Standard"`, when the `serde` feature is enabled.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Format {
     Unknown,
     Wild,
@@ -38,6 +46,34 @@ impl Format {
             Self::Twist => 4,
         }
     }
+
+    /// Returns whether `card` could be played in this format on `date`, according to `rotation::ROTATIONS`, or
+    /// `None` if the card pool of the format on `date` is not known, as for Twist and for Standard after
+    /// `rotation::KNOWN_THROUGH`.
+    /// The card's set is the set it belongs to today, so cards since moved to the Hall of Fame count as Wild only.
+    #[cfg(feature = "cards")]
+    #[must_use]
+    pub fn is_card_legal(&self, card: &Card, date: Date) -> Option<bool> {
+        match legal_sets(*self, date) {
+            LegalSets::All => Some(true),
+            LegalSets::Only(sets) => Some(sets.contains(&card.set.as_str())),
+            LegalSets::Unknown => None,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    /// The name of the format as shown by the Hearthstone client.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Unknown => "Unknown",
+            Self::Wild => "Wild",
+            Self::Standard => "Standard",
+            Self::Classic => "Classic",
+            Self::Twist => "Twist",
+        };
+        f.write_str(name)
+    }
 }
 
 #[cfg(test)]
@@ -70,4 +106,39 @@ mod tests {
             Format::from_u32(input)
         );
     }
+
+    #[test]
+    fn display_matches_client_names() {
+        assert_eq!(Format::Standard.to_string(), "Standard");
+        assert_eq!(Format::Twist.to_string(), "Twist");
+    }
+
+    #[cfg(feature = "cards")]
+    #[test]
+    fn is_card_legal_follows_rotation() {
+        let card: Card = serde_json::from_str(
+            r#"{"dbfId":192,"id":"EX1_295","name":"Ice Block","set":"EXPERT1","type":"SPELL"}"#,
+        )
+        .expect("Parsed correctly");
+        assert_eq!(
+            Format::Standard.is_card_legal(&card, Date::new(2020, 4, 7)),
+            Some(true)
+        );
+        assert_eq!(
+            Format::Standard.is_card_legal(&card, Date::new(2021, 3, 30)),
+            Some(false)
+        );
+        assert_eq!(
+            Format::Wild.is_card_legal(&card, Date::new(2021, 3, 30)),
+            Some(true)
+        );
+        assert_eq!(
+            Format::Classic.is_card_legal(&card, Date::new(2021, 3, 30)),
+            Some(false)
+        );
+        assert_eq!(
+            Format::Twist.is_card_legal(&card, Date::new(2024, 1, 1)),
+            None
+        );
+    }
 }
@@ -1,23 +1,74 @@
 #![warn(clippy::all, clippy::pedantic)]
 
+pub mod builder;
+#[cfg(feature = "cards")]
+pub mod cards;
+pub mod collection;
+pub mod constants;
+#[cfg(feature = "cards")]
+pub mod crafting;
 pub mod deck;
+pub mod decoder;
+pub mod diff;
+#[cfg(feature = "cards")]
+pub mod dust;
 pub mod error;
+pub mod export;
 pub mod format;
+pub mod normalize;
+pub mod odds;
+pub mod patch;
+mod reader;
+pub mod rotation;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod simulation;
+#[cfg(feature = "cards")]
+pub mod stats;
+#[cfg(feature = "cards")]
+pub mod validation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 use crate::deck::Deck;
+use crate::decoder::{DecodeLimits, Decoder};
 use crate::error::DeckCodeError;
+use crate::normalize::Normalizations;
 
 use base64::prelude::*;
 use integer_encoding::VarInt;
 
-/// Convert a Hearthstone deck code into a `Deck` struct
+/// Convert a Hearthstone deck code into a `Deck` struct.
+/// Uses the default `decoder::DecodeLimits`. To decode many codes, reuse a `decoder::Decoder` instead.
 /// # Errors
 ///
 /// Will return `Err` if the version of the deck code is not supported or if
 /// the deck code is invalid.
 pub fn decode_deck_code(deck_code: &str) -> Result<Deck, DeckCodeError> {
-    let decoded: Vec<u32> = decode_code_to_u32_vec(deck_code)?;
-    Deck::new(&decoded)
+    Decoder::new().decode(deck_code)
+}
+
+/// Convert a Hearthstone deck code into a `Deck` struct, rejecting codes that exceed `limits`.
+/// # Errors
+///
+/// Will return `Err` if the deck code exceeds `limits`, if the version of the deck code is not supported or if
+/// the deck code is invalid.
+pub fn decode_deck_code_with(
+    deck_code: &str,
+    limits: &DecodeLimits,
+) -> Result<Deck, DeckCodeError> {
+    Decoder::with_limits(*limits).decode(deck_code)
+}
+
+/// Convert a pasted Hearthstone deck code into a `Deck` struct, tolerating surrounding and internal whitespace,
+/// missing `=` padding and the URL-safe base64 alphabet. Returns the deck and the normalizations that were needed.
+/// Use `decode_deck_code` to validate that a code is already in the strict format.
+/// # Errors
+///
+/// Will return `Err` if the version of the deck code is not supported or if
+/// the deck code is invalid once normalized.
+pub fn decode_deck_code_lenient(deck_code: &str) -> Result<(Deck, Normalizations), DeckCodeError> {
+    Decoder::new().decode_lenient(deck_code)
 }
 
 /// Convert a deck struct into an importable Hearthstone deck code
@@ -26,25 +77,11 @@ pub fn encode_deck_code(deck: &Deck) -> String {
     encode_u32_vec_to_deck_code(deck.to_byte_array())
 }
 
-/// Convert a Base64 deck code into a vector of u32 values that can then be mapped to the format of the deck
-fn decode_code_to_u32_vec(deck_code: &str) -> Result<Vec<u32>, DeckCodeError> {
-    let mut decoded = BASE64_STANDARD.decode(deck_code)?;
-
-    let mut deck_code_decoded: Vec<u32> = vec![];
-    // Read u8 values as u32 varints
-    while !decoded.is_empty() {
-        let (read, size) = u32::decode_var(&decoded).ok_or(DeckCodeError::Unknown)?;
-        deck_code_decoded.push(read);
-        decoded = decoded[size..].to_vec();
-    }
-    Ok(deck_code_decoded)
-}
-
 /// Convert a vector of u32 values into a Base64 deck code
 #[allow(clippy::needless_range_loop)]
 fn encode_u32_vec_to_deck_code(byte_array: Vec<u32>) -> String {
     let mut fixed_size_integers: Vec<u8> = Vec::new();
-    let mut encoded: [u8; 4] = [0, 0, 0, 0]; // This length of array is calculated by taking the largest dbfid and calculating ceil(log(dbfid, 128)) as 128 is the largest value a u8 can store.
+    let mut encoded: [u8; 5] = [0; 5]; // This length of array is ceil(32 / 7): the number of 7 bit groups needed to varint encode any u32.
     for i in byte_array {
         let encoded_bytes = i.encode_var(&mut encoded[..]);
         for encoded_index in 0..encoded_bytes {
@@ -57,10 +94,22 @@ fn encode_u32_vec_to_deck_code(byte_array: Vec<u32>) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::{Reader, VarintReader};
+
+    /// Every integer in a deck code, read through the `Reader` used by `Deck::read`.
+    fn read_integers(deck_code: &str) -> Vec<u32> {
+        let decoded = BASE64_STANDARD.decode(deck_code).expect("Valid base64");
+        let mut reader = VarintReader::new(&decoded);
+        let mut integers = Vec::new();
+        while let Some(value) = reader.read().expect("Valid varints") {
+            integers.push(value);
+        }
+        integers
+    }
 
     #[test]
-    fn decode_code_to_u32_vec_correctly_decodes_a_simple_code() {
-        let result = decode_code_to_u32_vec("AAEBAQcAAAQBAwIDAwMEAw==");
+    fn reader_correctly_decodes_a_simple_code() {
+        let result = read_integers("AAEBAQcAAAQBAwIDAwMEAw==");
         let expected = vec![
             0, // Null byte
             1, // Version 1
@@ -79,8 +128,20 @@ mod tests {
             3, 3, //
             4, 3, //
         ];
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), expected);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn decode_deck_code_reports_byte_offset_of_invalid_varint() {
+        // Header, one hero and then a varint that never terminates
+        let code = BASE64_STANDARD.encode([0, 1, 1, 1, 0xfd, 0xff]);
+        assert_eq!(
+            decode_deck_code(&code),
+            Err(DeckCodeError::InvalidVarint {
+                section: crate::error::DeckSection::Heroes,
+                offset: 4
+            })
+        );
     }
 
     #[test]
@@ -109,10 +170,9 @@ mod tests {
     }
 
     #[test]
-    fn decode_code_to_u8_vec_correctly_decodes_a_complex_code() {
-        let result = decode_code_to_u32_vec(
-            "AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIA",
-        );
+    fn reader_correctly_decodes_a_complex_code() {
+        let result =
+            read_integers("AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIA");
         let expected = vec![
             0, 1, 2, // Standard header
             1, 637,   // 1 Hero: Jaina,
@@ -137,8 +197,7 @@ mod tests {
             41496, // Primordial Glyph,
             0,     // No 3+-copy cards
         ];
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), expected);
+        assert_eq!(result, expected);
     }
 
     #[test]
@@ -170,4 +229,11 @@ mod tests {
         let expected = "AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIA";
         assert_eq!(encode_u32_vec_to_deck_code(input), expected);
     }
+
+    #[test]
+    fn encode_and_decode_largest_dbfid() {
+        let input = vec![0, 1, 1, 1, u32::MAX, 0, 0, 0, 0];
+        let code = encode_u32_vec_to_deck_code(input.clone());
+        assert_eq!(read_integers(&code), input);
+    }
 }
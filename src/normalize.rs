@@ -0,0 +1,113 @@
+/// The changes made to a pasted deck code so that it could be decoded leniently.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Normalizations {
+    /// Whitespace was removed from the start or end of the code.
+    pub trimmed_whitespace: bool,
+    /// Whitespace, such as line breaks, was removed from inside the code.
+    pub removed_internal_whitespace: bool,
+    /// The URL-safe base64 characters `-` and `_` were replaced with `+` and `/`.
+    pub converted_url_safe_alphabet: bool,
+    /// Missing or incorrect `=` padding was fixed.
+    pub fixed_padding: bool,
+}
+
+impl Normalizations {
+    /// Returns true when the code was already in the strict format.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Writes `deck_code` into `normalized` as a padded, standard alphabet base64 string.
+pub(crate) fn normalize_into(deck_code: &str, normalized: &mut String) -> Normalizations {
+    let mut normalizations = Normalizations::default();
+
+    let trimmed = deck_code.trim();
+    normalizations.trimmed_whitespace = trimmed.len() != deck_code.len();
+
+    normalized.clear();
+    for character in trimmed.chars() {
+        match character {
+            c if c.is_whitespace() => normalizations.removed_internal_whitespace = true,
+            '-' => {
+                normalizations.converted_url_safe_alphabet = true;
+                normalized.push('+');
+            }
+            '_' => {
+                normalizations.converted_url_safe_alphabet = true;
+                normalized.push('/');
+            }
+            c => normalized.push(c),
+        }
+    }
+
+    let unpadded_length = normalized.trim_end_matches('=').len();
+    let padding = (4 - unpadded_length % 4) % 4;
+    if normalized.len() != unpadded_length + padding {
+        normalizations.fixed_padding = true;
+        normalized.truncate(unpadded_length);
+        normalized.extend(std::iter::repeat_n('=', padding));
+    }
+
+    normalizations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(deck_code: &str) -> (String, Normalizations) {
+        let mut normalized = String::new();
+        let normalizations = normalize_into(deck_code, &mut normalized);
+        (normalized, normalizations)
+    }
+
+    #[test]
+    fn strict_code_is_unchanged() {
+        let (normalized, normalizations) = normalize("AAEBAQcAAAQBAwIDAwMEAw==");
+        assert_eq!(normalized, "AAEBAQcAAAQBAwIDAwMEAw==");
+        assert!(normalizations.is_empty());
+    }
+
+    #[test]
+    fn whitespace_is_removed() {
+        let (normalized, normalizations) = normalize("  AAEBAQcAAAQB\r\nAwIDAwMEAw==\n");
+        assert_eq!(normalized, "AAEBAQcAAAQBAwIDAwMEAw==");
+        assert_eq!(
+            normalizations,
+            Normalizations {
+                trimmed_whitespace: true,
+                removed_internal_whitespace: true,
+                ..Normalizations::default()
+            }
+        );
+    }
+
+    #[test]
+    fn url_safe_alphabet_and_missing_padding_are_fixed() {
+        let (normalized, normalizations) = normalize(
+            "AAEBAfHhBAK0gAW0gAUO9eMEguQEseYEjvEErqEF88gF6oAG6akG_7oG_8kGkMsGpdwGpPQGpvQGAAA",
+        );
+        assert_eq!(
+            normalized,
+            "AAEBAfHhBAK0gAW0gAUO9eMEguQEseYEjvEErqEF88gF6oAG6akG/7oG/8kGkMsGpdwGpPQGpvQGAAA="
+        );
+        assert_eq!(
+            normalizations,
+            Normalizations {
+                converted_url_safe_alphabet: true,
+                fixed_padding: true,
+                ..Normalizations::default()
+            }
+        );
+    }
+
+    #[test]
+    fn excess_padding_is_removed() {
+        let (normalized, normalizations) = normalize("AAEBAQcAAAQBAwIDAwMEAw====");
+        assert_eq!(normalized, "AAEBAQcAAAQBAwIDAwMEAw==");
+        assert!(normalizations.fixed_padding);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::deck::Deck;
+
+/// The number of cards in the opening hand of the player going first.
+pub const ON_PLAY_HAND_SIZE: u32 = 3;
+/// The number of cards in the opening hand of the player going second, not counting The Coin.
+pub const ON_COIN_HAND_SIZE: u32 = 4;
+
+/// Whether the player goes first or second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayOrder {
+    OnPlay,
+    OnCoin,
+}
+
+impl PlayOrder {
+    /// The number of cards in the opening hand.
+    #[must_use]
+    pub fn hand_size(self) -> u32 {
+        match self {
+            Self::OnPlay => ON_PLAY_HAND_SIZE,
+            Self::OnCoin => ON_COIN_HAND_SIZE,
+        }
+    }
+}
+
+/// Which cards of the opening hand are replaced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mulligan<'a> {
+    /// Keep the opening hand.
+    KeepAll,
+    /// Replace every card that is not one of the cards being looked for.
+    ReplaceNonTargets,
+    /// Keep the cards with these dbfids and replace every other card, including cards being looked for.
+    Keep(&'a [u32]),
+}
+
+impl Deck {
+    /// The probability of having drawn at least `at_least` cards out of every copy of `dbfids` by `turn`, including the
+    /// card drawn at the start of that turn. Turn 0 is the opening hand after the mulligan.
+    ///
+    /// Replaced cards are shuffled back into the deck after their replacements are drawn, as in the game.
+    /// Sideboards are not drawn from.
+    #[must_use]
+    pub fn draw_probability(
+        &self,
+        dbfids: &[u32],
+        at_least: u32,
+        turn: u32,
+        order: PlayOrder,
+        mulligan: Mulligan,
+    ) -> f64 {
+        let targets = sorted_unique(dbfids);
+        let deck_size = u32::try_from(self.total_cards()).unwrap_or(u32::MAX);
+        let copies = self.copies_of(&targets);
+        let hand_size = order.hand_size().min(deck_size);
+
+        // The opening hand is split into kept and replaced targets, and kept and replaced other cards.
+        let (kept_targets, kept_others) = match mulligan {
+            Mulligan::KeepAll => (copies, deck_size - copies),
+            Mulligan::ReplaceNonTargets => (copies, 0),
+            Mulligan::Keep(keep) => {
+                let keep = sorted_unique(keep);
+                let (kept_targets, kept_others): (Vec<u32>, Vec<u32>) = keep
+                    .into_iter()
+                    .partition(|dbfid| targets.binary_search(dbfid).is_ok());
+                (self.copies_of(&kept_targets), self.copies_of(&kept_others))
+            }
+        };
+        let replaced_targets = copies - kept_targets;
+        let replaced_others = deck_size - copies - kept_others;
+        let rest = deck_size - hand_size;
+        let draws = turn.min(rest);
+
+        let mut probability = 0.0;
+        for kept in 0..=hand_size.min(kept_targets) {
+            for replaced_target in 0..=(hand_size - kept).min(replaced_targets) {
+                for replaced_other in 0..=(hand_size - kept - replaced_target).min(replaced_others)
+                {
+                    let kept_other = hand_size - kept - replaced_target - replaced_other;
+                    if kept_other > kept_others {
+                        continue;
+                    }
+                    let drawn_opening = (ln_choose(kept_targets, kept)
+                        + ln_choose(replaced_targets, replaced_target)
+                        + ln_choose(replaced_others, replaced_other)
+                        + ln_choose(kept_others, kept_other)
+                        - ln_choose(deck_size, hand_size))
+                    .exp();
+                    // The replacements are drawn before the replaced cards go back into the deck.
+                    let replaced = replaced_target + replaced_other;
+                    let left_in_rest = copies - kept - replaced_target;
+                    for from_mulligan in 0..=replaced.min(left_in_rest) {
+                        let drawn_mulligan =
+                            hypergeometric(rest, left_in_rest, replaced, from_mulligan);
+                        let remaining = copies - kept - from_mulligan;
+                        for from_draws in 0..=draws.min(remaining) {
+                            if kept + from_mulligan + from_draws >= at_least {
+                                probability += drawn_opening
+                                    * drawn_mulligan
+                                    * hypergeometric(rest, remaining, draws, from_draws);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        probability.min(1.0)
+    }
+
+    /// The number of copies of the cards with the given dbfids in the main deck.
+    fn copies_of(&self, dbfids: &[u32]) -> u32 {
+        dbfids
+            .iter()
+            .map(|&dbfid| u32::from(self.count_of(dbfid)))
+            .sum()
+    }
+}
+
+fn sorted_unique(dbfids: &[u32]) -> Vec<u32> {
+    let mut dbfids = dbfids.to_vec();
+    dbfids.sort_unstable();
+    dbfids.dedup();
+    dbfids
+}
+
+/// The probability of finding exactly `found` of `successes` cards when drawing `draws` cards from `population`.
+fn hypergeometric(population: u32, successes: u32, draws: u32, found: u32) -> f64 {
+    if found > successes || found > draws || draws > population {
+        return 0.0;
+    }
+    if draws - found > population - successes {
+        return 0.0;
+    }
+    (ln_choose(successes, found) + ln_choose(population - successes, draws - found)
+        - ln_choose(population, draws))
+    .exp()
+}
+
+/// The natural log of the binomial coefficient `n` choose `k`.
+fn ln_choose(n: u32, k: u32) -> f64 {
+    let k = k.min(n - k);
+    (1..=k)
+        .map(|i| (f64::from(n - k + i) / f64::from(i)).ln())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    fn deck() -> Deck {
+        let cards: Vec<(u8, u32)> = (1..=15).map(|dbfid| (2, dbfid)).collect();
+        Deck::from_cards(Format::Standard, vec![637], &cards, Vec::new())
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn opening_hand_without_mulligan() {
+        // 1 - C(28, 3) / C(30, 3)
+        assert_close(
+            deck().draw_probability(&[1], 1, 0, PlayOrder::OnPlay, Mulligan::KeepAll),
+            1.0 - 3276.0 / 4060.0,
+        );
+        // 1 - C(28, 4) / C(30, 4)
+        assert_close(
+            deck().draw_probability(&[1], 1, 0, PlayOrder::OnCoin, Mulligan::KeepAll),
+            1.0 - 20475.0 / 27405.0,
+        );
+    }
+
+    #[test]
+    fn mulligan_draws_replacements_from_rest_of_deck() {
+        // 1 - C(28, 3) / C(30, 3) * C(25, 3) / C(27, 3)
+        assert_close(
+            deck().draw_probability(&[1], 1, 0, PlayOrder::OnPlay, Mulligan::ReplaceNonTargets),
+            1.0 - 3276.0 / 4060.0 * 2300.0 / 2925.0,
+        );
+    }
+
+    #[test]
+    fn keep_replaces_cards_outside_the_kept_set() {
+        let all: Vec<u32> = (1..=15).collect();
+        for turn in 0..3 {
+            assert_close(
+                deck().draw_probability(&[1], 1, turn, PlayOrder::OnCoin, Mulligan::Keep(&[1])),
+                deck().draw_probability(
+                    &[1],
+                    1,
+                    turn,
+                    PlayOrder::OnCoin,
+                    Mulligan::ReplaceNonTargets,
+                ),
+            );
+            assert_close(
+                deck().draw_probability(&[1], 1, turn, PlayOrder::OnCoin, Mulligan::Keep(&all)),
+                deck().draw_probability(&[1], 1, turn, PlayOrder::OnCoin, Mulligan::KeepAll),
+            );
+        }
+
+        // Replacing the whole hand, including any copies of card 1, which go back into the deck afterwards:
+        // the replacements are drawn from 27 cards holding the 2 - x copies not in the opening hand.
+        let miss = (3276.0 * 2300.0 + 756.0 * 2600.0 + 28.0 * 2925.0) / (4060.0 * 2925.0);
+        assert_close(
+            deck().draw_probability(&[1], 1, 0, PlayOrder::OnPlay, Mulligan::Keep(&[])),
+            1.0 - miss,
+        );
+    }
+
+    #[test]
+    fn turn_draws_are_included() {
+        // Without a mulligan, 3 + 4 cards are seen by turn 4: 1 - C(28, 7) / C(30, 7)
+        assert_close(
+            deck().draw_probability(&[1], 1, 4, PlayOrder::OnPlay, Mulligan::KeepAll),
+            1.0 - 1_184_040.0 / 2_035_800.0,
+        );
+    }
+
+    #[test]
+    fn any_of_several_cards_are_counted_together() {
+        // Both copies of two cards: 1 - C(26, 3) / C(30, 3)
+        assert_close(
+            deck().draw_probability(&[1, 2, 2], 1, 0, PlayOrder::OnPlay, Mulligan::KeepAll),
+            1.0 - 2600.0 / 4060.0,
+        );
+        // Both copies of one card: C(2, 2) * C(28, 1) / C(30, 3)
+        assert_close(
+            deck().draw_probability(&[1], 2, 0, PlayOrder::OnPlay, Mulligan::KeepAll),
+            28.0 / 4060.0,
+        );
+    }
+
+    #[test]
+    fn impossible_and_certain_draws() {
+        let deck = deck();
+        assert_close(
+            deck.draw_probability(&[1], 3, 20, PlayOrder::OnPlay, Mulligan::KeepAll),
+            0.0,
+        );
+        assert_close(
+            deck.draw_probability(&[99], 0, 0, PlayOrder::OnPlay, Mulligan::KeepAll),
+            1.0,
+        );
+        assert_close(
+            deck.draw_probability(&[1], 2, 27, PlayOrder::OnPlay, Mulligan::ReplaceNonTargets),
+            1.0,
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::deck::Deck;
+use crate::diff::{CardChange, DeckDiff};
+use crate::error::DeckCodeError;
+use crate::{decode_deck_code, encode_deck_code};
+
+/// A change to the number of copies of a card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatchOperation {
+    /// Add `count` copies, written as `+2 113`.
+    Add { count: u8, dbfid: u32 },
+    /// Remove `count` copies, written as `-1 192`.
+    Remove { count: u8, dbfid: u32 },
+    /// Set the number of copies to `count`, written as `=1 315`. A `count` of 0 removes the card.
+    Set { count: u8, dbfid: u32 },
+}
+
+/// A card targeted by a patch operation, either in the main deck or in the sideboard of `owner`.
+/// Sideboard cards are written as `dbfid@owner`, e.g. `+1 102983@102982`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatchEntry {
+    pub operation: PatchOperation,
+    pub owner: Option<u32>,
+}
+
+/// A list of changes to a deck's cards, applied in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeckPatch {
+    pub entries: Vec<PatchEntry>,
+}
+
+impl DeckPatch {
+    /// Parse a patch written as pairs of a signed count and a dbfid, e.g. `+2 113 -1 192`.
+    /// Pairs may be separated by any whitespace, including line breaks.
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::InvalidPatch` if a pair is not a `+`, `-` or `=` count followed by a dbfid.
+    pub fn parse(text: &str) -> Result<Self, DeckCodeError> {
+        let mut tokens = text.split_whitespace();
+        let mut entries = Vec::new();
+        while let Some(count) = tokens.next() {
+            let invalid = |operation: String| DeckCodeError::InvalidPatch { operation };
+            let card = tokens.next().ok_or_else(|| invalid(count.to_owned()))?;
+            let entry =
+                parse_entry(count, card).ok_or_else(|| invalid(format!("{count} {card}")))?;
+            entries.push(entry);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Apply the patch to a copy of `deck`.
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::CardNotInDeck` or `DeckCodeError::NotEnoughCopies` if an operation removes more copies
+    /// of a card than the deck has, and the errors of `Deck::add_card` and `Deck::add_sideboard_card` for additions.
+    pub fn apply(&self, deck: &Deck) -> Result<Deck, DeckCodeError> {
+        let mut patched = deck.clone();
+        for entry in &self.entries {
+            match (entry.operation, entry.owner) {
+                (PatchOperation::Add { count, dbfid }, None) => patched.add_card(count, dbfid)?,
+                (PatchOperation::Remove { count, dbfid }, None) => {
+                    patched.remove_card(count, dbfid)?;
+                }
+                (PatchOperation::Set { count, dbfid }, None) => patched.set_count(dbfid, count),
+                (PatchOperation::Add { count, dbfid }, Some(owner)) => {
+                    patched.add_sideboard_card(count, dbfid, owner)?;
+                }
+                (PatchOperation::Remove { count, dbfid }, Some(owner)) => {
+                    patched.remove_sideboard_card(count, dbfid, owner)?;
+                }
+                (PatchOperation::Set { count, dbfid }, Some(owner)) => {
+                    let current = patched.sideboard_count_of(dbfid, owner);
+                    if count > current {
+                        patched.add_sideboard_card(count - current, dbfid, owner)?;
+                    } else if count < current {
+                        patched.remove_sideboard_card(current - count, dbfid, owner)?;
+                    }
+                }
+            }
+        }
+        Ok(patched)
+    }
+
+    /// Decode `deck_code`, apply the patch and encode the result.
+    /// # Errors
+    ///
+    /// Returns an error if the deck code is invalid or the patch cannot be applied to it.
+    pub fn apply_to_code(&self, deck_code: &str) -> Result<String, DeckCodeError> {
+        Ok(encode_deck_code(
+            &self.apply(&decode_deck_code(deck_code)?)?,
+        ))
+    }
+}
+
+impl FromStr for DeckPatch {
+    type Err = DeckCodeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+impl fmt::Display for DeckPatch {
+    /// Writes the patch in the form read by `DeckPatch::parse`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, entry) in self.entries.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            let (sign, count, dbfid) = match entry.operation {
+                PatchOperation::Add { count, dbfid } => ('+', count, dbfid),
+                PatchOperation::Remove { count, dbfid } => ('-', count, dbfid),
+                PatchOperation::Set { count, dbfid } => ('=', count, dbfid),
+            };
+            write!(f, "{sign}{count} {dbfid}")?;
+            if let Some(owner) = entry.owner {
+                write!(f, "@{owner}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&DeckDiff> for DeckPatch {
+    /// The patch that turns the original deck of a diff into the other deck, apart from its heroes and format.
+    fn from(diff: &DeckDiff) -> Self {
+        let entry = |change: &CardChange, owner| {
+            let operation = if change.after > change.before {
+                PatchOperation::Add {
+                    count: change.after - change.before,
+                    dbfid: change.dbfid,
+                }
+            } else {
+                PatchOperation::Remove {
+                    count: change.before - change.after,
+                    dbfid: change.dbfid,
+                }
+            };
+            PatchEntry { operation, owner }
+        };
+
+        // Removals come first so that sideboards are not removed along with a card that is swapped out.
+        let mut entries: Vec<PatchEntry> = Vec::new();
+        for sideboard in &diff.sideboards {
+            entries.extend(
+                sideboard
+                    .changes
+                    .iter()
+                    .filter(|change| change.after < change.before)
+                    .map(|change| entry(change, Some(sideboard.owner))),
+            );
+        }
+        entries.extend(diff.cards.iter().map(|change| entry(change, None)));
+        for sideboard in &diff.sideboards {
+            entries.extend(
+                sideboard
+                    .changes
+                    .iter()
+                    .filter(|change| change.after > change.before)
+                    .map(|change| entry(change, Some(sideboard.owner))),
+            );
+        }
+        Self { entries }
+    }
+}
+
+/// Parses a signed count such as `+2` and a card such as `113` or `102983@102982`.
+fn parse_entry(count: &str, card: &str) -> Option<PatchEntry> {
+    let mut characters = count.chars();
+    let sign = characters.next()?;
+    let count = characters.as_str().parse().ok()?;
+    let (dbfid, owner) = match card.split_once('@') {
+        Some((dbfid, owner)) => (dbfid.parse().ok()?, Some(owner.parse().ok()?)),
+        None => (card.parse().ok()?, None),
+    };
+    let operation = match sign {
+        '+' => PatchOperation::Add { count, dbfid },
+        '-' => PatchOperation::Remove { count, dbfid },
+        '=' => PatchOperation::Set { count, dbfid },
+        _ => return None,
+    };
+    Some(PatchEntry { operation, owner })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    fn entry(operation: PatchOperation, owner: Option<u32>) -> PatchEntry {
+        PatchEntry { operation, owner }
+    }
+
+    #[test]
+    fn parse_reads_signed_counts_and_dbfids() {
+        let patch = DeckPatch::parse("+2 113 -1 192\n=3 315 +1 5@4").expect("Parsed correctly");
+        assert_eq!(
+            patch.entries,
+            vec![
+                entry(
+                    PatchOperation::Add {
+                        count: 2,
+                        dbfid: 113
+                    },
+                    None
+                ),
+                entry(
+                    PatchOperation::Remove {
+                        count: 1,
+                        dbfid: 192
+                    },
+                    None
+                ),
+                entry(
+                    PatchOperation::Set {
+                        count: 3,
+                        dbfid: 315
+                    },
+                    None
+                ),
+                entry(PatchOperation::Add { count: 1, dbfid: 5 }, Some(4)),
+            ]
+        );
+        assert_eq!(patch.to_string(), "+2 113 -1 192 =3 315 +1 5@4");
+    }
+
+    #[test]
+    fn parse_returns_err_for_invalid_operations() {
+        assert_eq!(
+            DeckPatch::parse("+2 113 2 192"),
+            Err(DeckCodeError::InvalidPatch {
+                operation: "2 192".to_owned()
+            })
+        );
+        assert_eq!(
+            DeckPatch::parse("+2"),
+            Err(DeckCodeError::InvalidPatch {
+                operation: "+2".to_owned()
+            })
+        );
+        assert!(DeckPatch::parse("+300 113").is_err());
+    }
+
+    #[test]
+    fn apply_swaps_cards() {
+        let deck = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(2, 192), (1, 315)],
+            Vec::new(),
+        );
+        let patch: DeckPatch = "-1 192 +1 113 =2 315".parse().expect("Parsed correctly");
+        assert_eq!(
+            patch.apply(&deck),
+            Ok(Deck::from_cards(
+                Format::Standard,
+                vec![637],
+                &[(1, 192), (1, 113), (2, 315)],
+                Vec::new()
+            ))
+        );
+    }
+
+    #[test]
+    fn apply_returns_err_when_removing_card_not_in_deck() {
+        let deck = Deck::from_cards(Format::Standard, vec![637], &[(2, 192)], Vec::new());
+        let patch = DeckPatch::parse("+1 113 -1 662").expect("Parsed correctly");
+        assert_eq!(
+            patch.apply(&deck),
+            Err(DeckCodeError::CardNotInDeck { dbfid: 662 })
+        );
+    }
+
+    #[test]
+    fn apply_to_code_returns_new_code() {
+        let patch = DeckPatch::parse("-1 192 +1 113").expect("Parsed correctly");
+        let code = patch
+            .apply_to_code("AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIA")
+            .expect("Applied");
+        let deck = decode_deck_code(&code).expect("Decoded correctly");
+        assert_eq!(deck.count_of(192), 0);
+        assert_eq!(deck.count_of(113), 3);
+        assert_eq!(deck.total_cards(), 30);
+    }
+
+    #[test]
+    fn patch_from_diff_recreates_other_deck() {
+        let before = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(2, 662), (1, 4), (1, 192)],
+            vec![(5, 1, 4), (6, 2, 4)],
+        );
+        let after = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(1, 315), (1, 192), (1, 7)],
+            vec![(5, 1, 7)],
+        );
+        let patch = DeckPatch::from(&before.diff(&after));
+        assert_eq!(patch.apply(&before), Ok(after));
+    }
+}
@@ -0,0 +1,230 @@
+//! Cursors used to decode deck codes. Nothing in this module may panic on untrusted input.
+#![deny(
+    clippy::indexing_slicing,
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic
+)]
+
+use crate::error::{DeckCodeError, DeckSection};
+
+use integer_encoding::VarInt;
+
+/// The bytes at the reader's position are not a valid u32 varint.
+#[derive(Debug, PartialEq)]
+pub(crate) struct InvalidVarint;
+
+/// A cursor over the integers that make up a deck code.
+pub(crate) trait Reader {
+    /// Read the next integer, returning `Ok(None)` once the input is exhausted.
+    fn read(&mut self) -> Result<Option<u32>, InvalidVarint>;
+
+    /// The offset of the next integer in the input.
+    fn position(&self) -> usize;
+
+    /// An upper bound on the number of integers left to read.
+    fn remaining(&self) -> usize;
+}
+
+/// Reads integers from an already decoded slice of u32 values.
+pub(crate) struct SliceReader<'a> {
+    values: &'a [u32],
+    position: usize,
+}
+
+impl<'a> SliceReader<'a> {
+    pub(crate) fn new(values: &'a [u32]) -> Self {
+        Self {
+            values,
+            position: 0,
+        }
+    }
+}
+
+impl Reader for SliceReader<'_> {
+    fn read(&mut self) -> Result<Option<u32>, InvalidVarint> {
+        let value = self.values.get(self.position).copied();
+        if value.is_some() {
+            self.position += 1;
+        }
+        Ok(value)
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn remaining(&self) -> usize {
+        self.values.len().saturating_sub(self.position)
+    }
+}
+
+/// Reads varint encoded integers from the base64 decoded bytes of a deck code without copying them.
+pub(crate) struct VarintReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> VarintReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+}
+
+impl Reader for VarintReader<'_> {
+    fn read(&mut self) -> Result<Option<u32>, InvalidVarint> {
+        let bytes = match self.bytes.get(self.position..) {
+            Some(bytes) if !bytes.is_empty() => bytes,
+            _ => return Ok(None),
+        };
+        // Decode as u64 so that values too large for a u32 are rejected rather than truncated
+        let (value, size) = u64::decode_var(bytes).ok_or(InvalidVarint)?;
+        let value = u32::try_from(value).map_err(|_| InvalidVarint)?;
+        self.position += size;
+        Ok(Some(value))
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn remaining(&self) -> usize {
+        // Every varint takes at least one byte
+        self.bytes.len().saturating_sub(self.position)
+    }
+}
+
+/// Reads the next integer of `section`, treating the end of the input as a truncated section.
+pub(crate) fn read_value<R: Reader>(
+    reader: &mut R,
+    section: DeckSection,
+) -> Result<u32, DeckCodeError> {
+    let offset = reader.position();
+    reader
+        .read()
+        .map_err(|_| DeckCodeError::InvalidVarint { section, offset })?
+        .ok_or(DeckCodeError::TruncatedSection { section, offset })
+}
+
+/// Reads a number of copies of a card, which must fit in a u8.
+pub(crate) fn read_copies<R: Reader>(
+    reader: &mut R,
+    section: DeckSection,
+) -> Result<u8, DeckCodeError> {
+    let offset = reader.position();
+    let count = read_value(reader, section)?;
+    u8::try_from(count).map_err(|_| DeckCodeError::CountOverflow {
+        section,
+        offset,
+        count,
+    })
+}
+
+/// The capacity to reserve for a section claiming `count` entries. Never reserves more than the input could hold.
+pub(crate) fn capacity(count: u32, remaining: usize) -> usize {
+    usize::try_from(count).map_or(remaining, |count| count.min(remaining))
+}
+
+/// Reads the number of entries in `section`, rejecting it if the section would then hold more than `limit` entries.
+/// `existing` is the number of entries already read for sections that are split over several lengths.
+pub(crate) fn read_section_length<R: Reader>(
+    reader: &mut R,
+    section: DeckSection,
+    limit: usize,
+    existing: usize,
+) -> Result<u32, DeckCodeError> {
+    let offset = reader.position();
+    let count = read_value(reader, section)?;
+    let total = usize::try_from(count)
+        .ok()
+        .and_then(|count| count.checked_add(existing));
+    match total {
+        Some(total) if total <= limit => Ok(count),
+        _ => Err(DeckCodeError::SectionTooLarge {
+            section,
+            offset,
+            count,
+            limit,
+        }),
+    }
+}
+
+/// Reads a section made of its length followed by that many dbfids.
+pub(crate) fn read_card_section<R: Reader>(
+    reader: &mut R,
+    section: DeckSection,
+    limit: usize,
+) -> Result<Vec<u32>, DeckCodeError> {
+    let count = read_section_length(reader, section, limit, 0)?;
+    let mut cards = Vec::with_capacity(capacity(count, reader.remaining()));
+    for _ in 0..count {
+        cards.push(read_value(reader, section)?);
+    }
+    Ok(cards)
+}
+
+/// Reads one sideboard section, which starts with the number of entries in the section.
+/// A `copies` of `None` denotes the Nx section, where each entry stores its own number of copies.
+pub(crate) fn read_sideboard_section<R: Reader>(
+    reader: &mut R,
+    copies: Option<u8>,
+    limit: usize,
+    sideboard_cards: &mut Vec<(u32, u8, u32)>,
+) -> Result<(), DeckCodeError> {
+    let entry_count =
+        read_section_length(reader, DeckSection::Sideboard, limit, sideboard_cards.len())?;
+    sideboard_cards.reserve(capacity(entry_count, reader.remaining() / 2));
+
+    for _ in 0..entry_count {
+        let card_id = read_value(reader, DeckSection::Sideboard)?;
+        let count = match copies {
+            Some(copies) => copies,
+            None => read_copies(reader, DeckSection::Sideboard)?,
+        };
+        let owner = read_value(reader, DeckSection::Sideboard)?;
+        sideboard_cards.push((card_id, count, owner));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint_reader_reads_multi_byte_values() {
+        let bytes = [0, 0xfd, 0x04, 0xc0, 0x01];
+        let mut reader = VarintReader::new(&bytes);
+        assert_eq!(reader.read(), Ok(Some(0)));
+        assert_eq!(reader.read(), Ok(Some(637)));
+        assert_eq!(reader.position(), 3);
+        assert_eq!(reader.remaining(), 2);
+        assert_eq!(reader.read(), Ok(Some(192)));
+        assert_eq!(reader.read(), Ok(None));
+    }
+
+    #[test]
+    fn varint_reader_returns_err_for_incomplete_varint() {
+        let bytes = [0xfd];
+        let mut reader = VarintReader::new(&bytes);
+        assert_eq!(reader.read(), Err(InvalidVarint));
+    }
+
+    #[test]
+    fn varint_reader_returns_err_for_values_larger_than_u32() {
+        let bytes = [0x80, 0x80, 0x80, 0x80, 0x10];
+        let mut reader = VarintReader::new(&bytes);
+        assert_eq!(reader.read(), Err(InvalidVarint));
+    }
+
+    #[test]
+    fn slice_reader_reads_until_exhausted() {
+        let values = [0, 637];
+        let mut reader = SliceReader::new(&values);
+        assert_eq!(reader.read(), Ok(Some(0)));
+        assert_eq!(reader.read(), Ok(Some(637)));
+        assert_eq!(reader.read(), Ok(None));
+        assert_eq!(reader.remaining(), 0);
+    }
+}
@@ -0,0 +1,316 @@
+use std::fmt;
+
+use crate::format::Format;
+
+/// A calendar date, used to look up which sets were legal in a format on a given day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    #[must_use]
+    pub const fn new(year: u16, month: u8, day: u8) -> Self {
+        Self { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    /// The date written as `YYYY-MM-DD`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The last day `ROTATIONS` is known to describe Standard on. Standard has rotated in March or April every year, so
+/// the 2025 rotation holds until at least the end of February 2026. Later dates may fall under a rotation that is not
+/// listed, so `legal_sets` does not guess a pool for them.
+pub const KNOWN_THROUGH: Date = Date::new(2026, 2, 28);
+
+/// The sets legal in a format from the date a rotation takes effect until the next rotation of that format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rotation {
+    pub format: Format,
+    pub effective: Date,
+    /// The `HearthstoneJSON` set codes of the legal sets, e.g. `EXPERT1`.
+    pub sets: &'static [&'static str],
+}
+
+/// Every rotation of a format with a fixed card pool, in order of the date it took effect.
+///
+/// Standard rotates at the start of each Hearthstone year, and the sets released during the year are listed
+/// with the rotation that started it. Classic only ever contains the 2014 card pool.
+/// Twist pools change every season and are not listed, so `Ruleset::legal_sets` has to be set to check a Twist deck.
+///
+/// Before 2021 the Basic and Classic cards used the `CORE` and `EXPERT1` set codes. Current card data moves most of
+/// them to `LEGACY` and reuses `CORE` for the Core set, so rotations up to 2020 list all three codes.
+pub const ROTATIONS: &[Rotation] = &[
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2016, 4, 26),
+        sets: &[
+            "CORE", "LEGACY", "EXPERT1", "BRM", "TGT", "LOE", "OG", "KARA", "GANGS",
+        ],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2017, 4, 6),
+        sets: &[
+            "CORE",
+            "LEGACY",
+            "EXPERT1",
+            "OG",
+            "KARA",
+            "GANGS",
+            "UNGORO",
+            "ICECROWN",
+            "LOOTAPALOOZA",
+        ],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2018, 4, 12),
+        sets: &[
+            "CORE",
+            "LEGACY",
+            "EXPERT1",
+            "UNGORO",
+            "ICECROWN",
+            "LOOTAPALOOZA",
+            "GILNEAS",
+            "BOOMSDAY",
+            "TROLL",
+        ],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2019, 4, 9),
+        sets: &[
+            "CORE",
+            "LEGACY",
+            "EXPERT1",
+            "GILNEAS",
+            "BOOMSDAY",
+            "TROLL",
+            "DALARAN",
+            "ULDUM",
+            "DRAGONS",
+            "YEAR_OF_THE_DRAGON",
+        ],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2020, 4, 7),
+        sets: &[
+            "CORE",
+            "LEGACY",
+            "EXPERT1",
+            "DALARAN",
+            "ULDUM",
+            "DRAGONS",
+            "YEAR_OF_THE_DRAGON",
+            "DEMON_HUNTER_INITIATE",
+            "BLACK_TEMPLE",
+            "SCHOLOMANCE",
+            "DARKMOON_FAIRE",
+        ],
+    },
+    Rotation {
+        format: Format::Classic,
+        effective: Date::new(2021, 3, 25),
+        sets: &["VANILLA"],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2021, 3, 30),
+        sets: &[
+            "CORE",
+            "DEMON_HUNTER_INITIATE",
+            "BLACK_TEMPLE",
+            "SCHOLOMANCE",
+            "DARKMOON_FAIRE",
+            "THE_BARRENS",
+            "STORMWIND",
+            "ALTERAC_VALLEY",
+        ],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2022, 4, 12),
+        sets: &[
+            "CORE",
+            "THE_BARRENS",
+            "STORMWIND",
+            "ALTERAC_VALLEY",
+            "THE_SUNKEN_CITY",
+            "REVENDRETH",
+            "RETURN_OF_THE_LICH_KING",
+            "PATH_OF_ARTHAS",
+        ],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2023, 4, 11),
+        sets: &[
+            "CORE",
+            "EVENT",
+            "THE_SUNKEN_CITY",
+            "REVENDRETH",
+            "RETURN_OF_THE_LICH_KING",
+            "PATH_OF_ARTHAS",
+            "BATTLE_OF_THE_BANDS",
+            "TITANS",
+            "WILD_WEST",
+        ],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2024, 3, 19),
+        sets: &[
+            "CORE",
+            "EVENT",
+            "BATTLE_OF_THE_BANDS",
+            "TITANS",
+            "WILD_WEST",
+            "WHIZBANGS_WORKSHOP",
+            "ISLAND_VACATION",
+            "SPACE",
+        ],
+    },
+    Rotation {
+        format: Format::Standard,
+        effective: Date::new(2025, 3, 25),
+        sets: &[
+            "CORE",
+            "EVENT",
+            "WHIZBANGS_WORKSHOP",
+            "ISLAND_VACATION",
+            "SPACE",
+            "EMERALD_DREAM",
+            "THE_LOST_CITY",
+            "TIME_TRAVEL",
+        ],
+    },
+];
+
+/// The card pool of a format on a given date, as returned by `legal_sets`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegalSets {
+    /// Every set is legal, as in Wild.
+    All,
+    /// Only the sets with these `HearthstoneJSON` set codes are legal.
+    Only(&'static [&'static str]),
+    /// The card pool is not in `ROTATIONS`, as for Twist, `Format::Unknown` and Standard after `KNOWN_THROUGH`.
+    Unknown,
+}
+
+/// The date the last rotation in `ROTATIONS` took effect.
+pub const LATEST_ROTATION: Date = ROTATIONS[ROTATIONS.len() - 1].effective;
+
+/// The sets legal in `format` on `date`.
+///
+/// A format has no legal sets before its first rotation took effect.
+#[must_use]
+pub fn legal_sets(format: Format, date: Date) -> LegalSets {
+    match format {
+        Format::Wild => LegalSets::All,
+        Format::Standard if date > KNOWN_THROUGH => LegalSets::Unknown,
+        Format::Standard | Format::Classic => LegalSets::Only(
+            ROTATIONS
+                .iter()
+                .rev()
+                .find(|rotation| rotation.format == format && rotation.effective <= date)
+                .map_or(&[][..], |rotation| rotation.sets),
+        ),
+        Format::Twist | Format::Unknown => LegalSets::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotations_are_in_date_order() {
+        assert!(ROTATIONS
+            .windows(2)
+            .all(|pair| pair[0].effective <= pair[1].effective));
+    }
+
+    #[test]
+    fn legal_sets_uses_rotation_in_effect_on_date() {
+        let LegalSets::Only(before_rotation) = legal_sets(Format::Standard, Date::new(2021, 3, 29))
+        else {
+            panic!("Standard has a fixed pool");
+        };
+        assert!(before_rotation.contains(&"EXPERT1"));
+        assert!(before_rotation.contains(&"LEGACY"));
+
+        let LegalSets::Only(after_rotation) = legal_sets(Format::Standard, Date::new(2021, 3, 30))
+        else {
+            panic!("Standard has a fixed pool");
+        };
+        assert!(!after_rotation.contains(&"EXPERT1"));
+        assert!(!after_rotation.contains(&"LEGACY"));
+        assert!(after_rotation.contains(&"THE_BARRENS"));
+    }
+
+    #[test]
+    fn legal_sets_is_empty_before_format_existed() {
+        assert_eq!(
+            legal_sets(Format::Standard, Date::new(2015, 1, 1)),
+            LegalSets::Only(&[])
+        );
+        assert_eq!(
+            legal_sets(Format::Classic, Date::new(2020, 1, 1)),
+            LegalSets::Only(&[])
+        );
+    }
+
+    #[test]
+    fn twist_and_unknown_pools_are_not_known() {
+        assert_eq!(
+            legal_sets(Format::Twist, Date::new(2024, 1, 1)),
+            LegalSets::Unknown
+        );
+        assert_eq!(
+            legal_sets(Format::Unknown, Date::new(2024, 1, 1)),
+            LegalSets::Unknown
+        );
+    }
+
+    #[test]
+    fn standard_is_not_known_after_table_ends() {
+        assert_eq!(
+            legal_sets(Format::Standard, KNOWN_THROUGH),
+            LegalSets::Only(ROTATIONS[ROTATIONS.len() - 1].sets)
+        );
+        assert_eq!(
+            legal_sets(Format::Standard, Date::new(2026, 3, 1)),
+            LegalSets::Unknown
+        );
+        // Classic never rotates.
+        assert_eq!(
+            legal_sets(Format::Classic, Date::new(2030, 1, 1)),
+            LegalSets::Only(&["VANILLA"])
+        );
+        assert!(ROTATIONS
+            .iter()
+            .all(|rotation| rotation.effective <= KNOWN_THROUGH));
+    }
+
+    #[test]
+    fn date_is_written_as_iso_8601() {
+        assert_eq!(Date::new(2025, 3, 5).to_string(), "2025-03-05");
+    }
+
+    #[test]
+    fn every_set_is_legal_in_wild() {
+        assert_eq!(
+            legal_sets(Format::Wild, Date::new(2015, 1, 1)),
+            LegalSets::All
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! Serde support for `Deck`, enabled by the `serde` feature.
+//!
+//! A `Deck` serializes to a readable shape listing its format, heroes, cards and sideboards:
+//!
+//! ```json
+//! {
+//!   "format": "Standard",
+//!   "heroes": [637],
+//!   "cards": [{"dbfid": 192, "count": 1}, {"dbfid": 315, "count": 2}],
+//!   "sideboards": [{"owner": 90749, "cards": [{"dbfid": 315, "count": 1}]}]
+//! }
+//! ```
+//!
+//! `sideboards` is left out when the deck has none. Deserializing canonicalizes the deck as `DeckBuilder` does.
+//! The shape is also available as `DeckDefinition`.
+//! To store a deck as its deck code instead, use `#[serde(with = "deck_codes::serialization::deckstring")]`.
+
+use std::collections::BTreeMap;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::builder::DeckBuilder;
+use crate::deck::Deck;
+use crate::error::DeckCodeError;
+use crate::format::Format;
+
+/// The shape a `Deck` is serialized as. Deserialize a definition and call `DeckDefinition::build` to
+/// report why an invalid deck was rejected as a `DeckCodeError`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeckDefinition {
+    pub format: Format,
+    pub heroes: Vec<u32>,
+    pub cards: Vec<CardEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sideboards: Vec<SideboardEntry>,
+}
+
+/// A card and its number of copies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CardEntry {
+    pub dbfid: u32,
+    pub count: u8,
+}
+
+/// The cards in the sideboard of the card `owner`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SideboardEntry {
+    pub owner: u32,
+    pub cards: Vec<CardEntry>,
+}
+
+impl DeckDefinition {
+    /// Produce a canonical `Deck` from the definition with `DeckBuilder`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `DeckBuilder::build`.
+    pub fn build(self) -> Result<Deck, DeckCodeError> {
+        let mut builder = DeckBuilder::new(self.format)
+            .cards(self.cards.iter().map(|card| (card.count, card.dbfid)));
+        for hero in self.heroes {
+            builder = builder.hero(hero);
+        }
+        for sideboard in self.sideboards {
+            for card in sideboard.cards {
+                builder = builder.sideboard_card(card.count, card.dbfid, sideboard.owner);
+            }
+        }
+        builder.build()
+    }
+}
+
+impl From<&Deck> for DeckDefinition {
+    fn from(deck: &Deck) -> Self {
+        let mut sideboards: BTreeMap<u32, Vec<CardEntry>> = BTreeMap::new();
+        for &(dbfid, count, owner) in deck.sideboard_cards() {
+            sideboards
+                .entry(owner)
+                .or_default()
+                .push(CardEntry { dbfid, count });
+        }
+
+        Self {
+            format: deck.format,
+            heroes: deck.heroes.clone(),
+            cards: deck
+                .main_cards()
+                .into_iter()
+                .map(|(dbfid, count)| CardEntry { dbfid, count })
+                .collect(),
+            sideboards: sideboards
+                .into_iter()
+                .map(|(owner, cards)| SideboardEntry { owner, cards })
+                .collect(),
+        }
+    }
+}
+
+impl Serialize for Deck {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DeckDefinition::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Deck {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DeckDefinition::deserialize(deserializer)?
+            .build()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Serialize a `Deck` as its deck code, for use with `#[serde(with = "deck_codes::serialization::deckstring")]`.
+pub mod deckstring {
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::deck::Deck;
+    use crate::{decode_deck_code, encode_deck_code};
+
+    /// Serialize `deck` as the string returned by `encode_deck_code`.
+    /// # Errors
+    ///
+    /// Returns the serializer's error if it cannot write a string.
+    pub fn serialize<S: Serializer>(deck: &Deck, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_deck_code(deck))
+    }
+
+    /// Deserialize a deck from a string with `decode_deck_code`.
+    /// # Errors
+    ///
+    /// Returns the deserializer's error if the value is not a string or not a valid deck code.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Deck, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        decode_deck_code(&code).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DeckSection;
+
+    fn deck() -> Deck {
+        Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(2, 315), (1, 192), (3, 90749)],
+            vec![(315, 1, 90749)],
+        )
+    }
+
+    #[test]
+    fn deck_serializes_to_readable_shape() {
+        let json = serde_json::to_value(deck()).expect("Serialized");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "format": "Standard",
+                "heroes": [637],
+                "cards": [
+                    {"dbfid": 192, "count": 1},
+                    {"dbfid": 315, "count": 2},
+                    {"dbfid": 90749, "count": 3}
+                ],
+                "sideboards": [{"owner": 90749, "cards": [{"dbfid": 315, "count": 1}]}]
+            })
+        );
+    }
+
+    #[test]
+    fn deck_round_trips_and_is_canonicalized() {
+        let deck = deck();
+        let json = serde_json::to_string(&deck).expect("Serialized");
+        assert_eq!(
+            serde_json::from_str::<Deck>(&json).expect("Deserialized"),
+            deck
+        );
+
+        let unsorted: Deck = serde_json::from_str(
+            r#"{"format":"Wild","heroes":[7],"cards":[{"dbfid":2,"count":1},{"dbfid":1,"count":1},{"dbfid":2,"count":1}]}"#,
+        )
+        .expect("Deserialized");
+        assert_eq!(
+            unsorted,
+            Deck::from_cards(Format::Wild, vec![7], &[(1, 1), (2, 2)], Vec::new())
+        );
+    }
+
+    #[test]
+    fn deck_deserialize_rejects_invalid_decks() {
+        let error = serde_json::from_str::<Deck>(
+            r#"{"format":"Wild","heroes":[7],"cards":[],"sideboards":[{"owner":5,"cards":[{"dbfid":1,"count":1}]}]}"#,
+        )
+        .expect_err("Owner is not in the deck");
+        assert!(error.to_string().contains("not in the deck"));
+        assert!(
+            serde_json::from_str::<Deck>(r#"{"format":"Modern","heroes":[],"cards":[]}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn definition_build_reports_deck_code_error() {
+        let definition: DeckDefinition = serde_json::from_str(
+            r#"{"format":"Wild","heroes":[7],"cards":[{"dbfid":1,"count":0}]}"#,
+        )
+        .expect("Deserialized");
+        assert_eq!(
+            definition.build(),
+            Err(DeckCodeError::ZeroCardCount { dbfid: 1 })
+        );
+    }
+
+    #[test]
+    fn deckstring_serializes_deck_code() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Saved {
+            #[serde(with = "deckstring")]
+            deck: Deck,
+        }
+
+        let code = "AAEBAQcAAAQBAwIDAwMEAwA=";
+        let saved: Saved =
+            serde_json::from_str(&format!(r#"{{"deck":"{code}"}}"#)).expect("Deserialized");
+        assert_eq!(
+            saved.deck,
+            crate::decode_deck_code(code).expect("Valid code")
+        );
+        assert_eq!(
+            serde_json::to_string(&saved).expect("Serialized"),
+            format!(r#"{{"deck":"{code}"}}"#)
+        );
+        assert!(serde_json::from_str::<Saved>(r#"{"deck":"not a code"}"#).is_err());
+    }
+
+    #[test]
+    fn errors_round_trip() {
+        let errors = [
+            DeckCodeError::InvalidBase64(base64::DecodeError::InvalidByte(3, b'!')),
+            DeckCodeError::TruncatedSection {
+                section: DeckSection::Heroes,
+                offset: 4,
+            },
+            DeckCodeError::InvalidPatch {
+                operation: "*1".to_owned(),
+            },
+            DeckCodeError::Unknown,
+        ];
+        for error in errors {
+            let json = serde_json::to_string(&error).expect("Serialized");
+            assert_eq!(
+                serde_json::from_str::<DeckCodeError>(&json).expect("Deserialized"),
+                error
+            );
+        }
+        assert_eq!(
+            serde_json::to_value(DeckCodeError::UnknownCard { dbfid: 5 }).expect("Serialized"),
+            serde_json::json!({"UnknownCard": {"dbfid": 5}})
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+
+use crate::deck::Deck;
+use crate::odds::PlayOrder;
+
+/// Shuffles decks and deals opening hands many times to estimate how a deck plays out.
+///
+/// The same seed always produces the same report, on every platform.
+#[derive(Clone, Copy, Debug)]
+pub struct Simulator<'a> {
+    deck: &'a Deck,
+    seed: u64,
+    trials: u32,
+    order: PlayOrder,
+    turns: u32,
+}
+
+/// The outcome of `Simulator::run`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimulationReport {
+    pub trials: u32,
+    /// The number of trials that kept each opening hand, by the sorted dbfids of the hand after the mulligan.
+    pub hands: BTreeMap<Vec<u32>, u32>,
+    /// The number of trials that replaced each number of cards, indexed by the number of cards replaced.
+    pub replaced: Vec<u32>,
+    /// The number of trials with a card costing exactly `turn` mana in hand on each turn, starting at turn 1.
+    pub curve_hits: Vec<u32>,
+}
+
+impl SimulationReport {
+    /// The fraction of trials with a card costing exactly `turn` mana in hand on that turn.
+    #[must_use]
+    pub fn curve_hit_rate(&self, turn: u32) -> f64 {
+        let hits = usize::try_from(turn)
+            .ok()
+            .and_then(|turn| turn.checked_sub(1))
+            .and_then(|index| self.curve_hits.get(index))
+            .copied()
+            .unwrap_or(0);
+        if self.trials == 0 {
+            0.0
+        } else {
+            f64::from(hits) / f64::from(self.trials)
+        }
+    }
+}
+
+impl<'a> Simulator<'a> {
+    /// Simulate 10,000 games of `deck` on the play, looking at the opening hand only.
+    #[must_use]
+    pub fn new(deck: &'a Deck, seed: u64) -> Self {
+        Self {
+            deck,
+            seed,
+            trials: 10_000,
+            order: PlayOrder::OnPlay,
+            turns: 0,
+        }
+    }
+
+    /// Set the number of games simulated.
+    #[must_use]
+    pub fn trials(mut self, trials: u32) -> Self {
+        self.trials = trials;
+        self
+    }
+
+    /// Set whether the deck goes first or second.
+    #[must_use]
+    pub fn order(mut self, order: PlayOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Set the number of turns drawn after the mulligan, counting curve hits on each of them.
+    #[must_use]
+    pub fn turns(mut self, turns: u32) -> Self {
+        self.turns = turns;
+        self
+    }
+
+    /// Run the simulation. `keep` decides which cards of the opening hand are kept, given the dbfids of the whole
+    /// opening hand and the position of the card in it, so copies of the same card can be told apart. `cost` returns
+    /// the mana cost of a card for counting curve hits.
+    ///
+    /// The mulligan replaces cards as described on [`Deck::draw_probability`].
+    /// Cards are never played, so the hand holds every card drawn so far. Sideboards are not drawn from.
+    pub fn run<K, C>(&self, keep: K, cost: C) -> SimulationReport
+    where
+        K: Fn(&[u32], usize) -> bool,
+        C: Fn(u32) -> Option<u32>,
+    {
+        let mut library: Vec<u32> = Vec::with_capacity(self.deck.total_cards());
+        for (dbfid, count) in self.deck.main_cards() {
+            library.extend(std::iter::repeat_n(dbfid, usize::from(count)));
+        }
+        let hand_size = usize::try_from(self.order.hand_size())
+            .unwrap_or(usize::MAX)
+            .min(library.len());
+        let turns = usize::try_from(self.turns).unwrap_or(usize::MAX);
+
+        let mut rng = SplitMix64(self.seed);
+        let mut report = SimulationReport {
+            trials: self.trials,
+            replaced: vec![0; hand_size + 1],
+            curve_hits: vec![0; turns],
+            ..SimulationReport::default()
+        };
+        let mut hand: Vec<u32> = Vec::with_capacity(hand_size + turns);
+        let mut replaced: Vec<u32> = Vec::with_capacity(hand_size);
+        let mut deck: Vec<u32> = Vec::with_capacity(library.len());
+
+        for _ in 0..self.trials {
+            rng.shuffle(&mut library);
+            let (opening, rest) = library.split_at(hand_size);
+
+            hand.clear();
+            replaced.clear();
+            for (position, &dbfid) in opening.iter().enumerate() {
+                if keep(opening, position) {
+                    hand.push(dbfid);
+                } else {
+                    replaced.push(dbfid);
+                }
+            }
+            // A deck too small to replace every card keeps the cards it cannot replace.
+            let swaps = replaced.len().min(rest.len());
+            hand.extend(replaced.drain(swaps..));
+            let (replacements, rest) = rest.split_at(swaps);
+            hand.extend_from_slice(replacements);
+            report.replaced[replaced.len()] += 1;
+
+            let mut kept = hand.clone();
+            kept.sort_unstable();
+            *report.hands.entry(kept).or_insert(0) += 1;
+
+            if turns > 0 {
+                deck.clear();
+                deck.extend_from_slice(rest);
+                deck.extend_from_slice(&replaced);
+                rng.shuffle(&mut deck);
+                for (turn, hits) in (1..).zip(report.curve_hits.iter_mut()) {
+                    if let Some(&dbfid) = deck.get(turn - 1) {
+                        hand.push(dbfid);
+                    }
+                    if hand.iter().any(|&dbfid| {
+                        cost(dbfid).and_then(|cost| usize::try_from(cost).ok()) == Some(turn)
+                    }) {
+                        *hits += 1;
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+/// The `SplitMix64` generator. It is written out here rather than taken from a crate so that
+/// a seed produces the same games across releases.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        let bound = u64::try_from(bound).unwrap_or(u64::MAX);
+        // Multiplying keeps the high bits, avoiding the bias of a plain modulo for small bounds.
+        usize::try_from((u128::from(self.next()) * u128::from(bound)) >> 64).unwrap_or(0)
+    }
+
+    /// A Fisher-Yates shuffle.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+    use crate::odds::Mulligan;
+
+    fn deck() -> Deck {
+        let cards: Vec<(u8, u32)> = (1..=15).map(|dbfid| (2, dbfid)).collect();
+        Deck::from_cards(Format::Standard, vec![637], &cards, Vec::new())
+    }
+
+    #[test]
+    fn same_seed_gives_same_report() {
+        let deck = deck();
+        let run = |seed| {
+            Simulator::new(&deck, seed)
+                .trials(500)
+                .turns(3)
+                .run(|hand, position| hand[position] < 5, |dbfid| Some(dbfid % 8))
+        };
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn report_counts_every_trial() {
+        let deck = deck();
+        let report = Simulator::new(&deck, 1)
+            .trials(1000)
+            .order(PlayOrder::OnCoin)
+            .run(|_, _| true, |_| None);
+        assert_eq!(report.trials, 1000);
+        assert_eq!(report.hands.values().sum::<u32>(), 1000);
+        assert!(report.hands.keys().all(|hand| hand.len() == 4));
+        assert_eq!(report.replaced, vec![1000, 0, 0, 0, 0]);
+        assert!(report.curve_hits.is_empty());
+    }
+
+    #[test]
+    fn mulligan_matches_draw_probability() {
+        let deck = deck();
+        let report = Simulator::new(&deck, 42).trials(20_000).turns(2).run(
+            |hand, position| hand[position] == 1,
+            |dbfid| (dbfid == 1).then_some(2),
+        );
+        let expected =
+            deck.draw_probability(&[1], 1, 2, PlayOrder::OnPlay, Mulligan::ReplaceNonTargets);
+        assert!((report.curve_hit_rate(2) - expected).abs() < 0.02);
+        assert!(report.curve_hit_rate(1).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn keep_sees_the_opening_hand() {
+        let deck = deck();
+        // Keep the whole hand when it holds card 1, and replace all of it otherwise.
+        let report = Simulator::new(&deck, 5)
+            .trials(1000)
+            .run(|hand, _| hand.contains(&1), |_| None);
+        assert_eq!(report.replaced[1] + report.replaced[2], 0);
+        assert_eq!(report.replaced[0] + report.replaced[3], 1000);
+        assert!(report.replaced[0] > 0 && report.replaced[3] > 0);
+    }
+
+    #[test]
+    fn keep_can_tell_copies_apart() {
+        let deck = Deck::from_cards(Format::Standard, vec![637], &[(30, 1)], Vec::new());
+        // Keep only the first copy of card 1 in the opening hand, which always holds three copies.
+        let report = Simulator::new(&deck, 9).trials(200).run(
+            |hand, position| hand[position] == 1 && !hand[..position].contains(&1),
+            |_| None,
+        );
+        assert_eq!(report.replaced, vec![0, 0, 200, 0]);
+    }
+
+    #[test]
+    fn small_deck_keeps_cards_it_cannot_replace() {
+        let deck = Deck::from_cards(Format::Standard, vec![637], &[(2, 1), (2, 2)], Vec::new());
+        let report = Simulator::new(&deck, 3)
+            .trials(100)
+            .turns(2)
+            .run(|_, _| false, |_| None);
+        assert_eq!(report.replaced, vec![0, 100, 0, 0]);
+        assert!(report.hands.keys().all(|hand| hand.len() == 3));
+    }
+}
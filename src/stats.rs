@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use crate::cards::{CardDb, CardType, Rarity};
+use crate::deck::Deck;
+use crate::error::DeckCodeError;
+
+/// The number of mana curve buckets, one for each cost from 0 to 6 and one for 7 or more.
+pub const MANA_CURVE_BUCKETS: usize = 8;
+
+/// Statistics about the cards in a deck, not counting sideboards.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeckStats {
+    /// The number of cards costing 0 to 6 mana, with cards costing 7 or more in the last bucket.
+    pub mana_curve: [u32; MANA_CURVE_BUCKETS],
+    /// The average mana cost of a card, or 0 for an empty deck.
+    pub average_cost: f64,
+    pub minions: u32,
+    pub spells: u32,
+    pub weapons: u32,
+    pub locations: u32,
+    pub heroes: u32,
+    /// Cards belonging to a class.
+    pub class_cards: u32,
+    /// Cards belonging to no class.
+    pub neutral_cards: u32,
+    /// The number of cards of each rarity. Cards without a rarity are not counted.
+    pub rarities: BTreeMap<Rarity, u32>,
+    /// The number of cards with each mechanic, e.g. `BATTLECRY`.
+    pub mechanics: BTreeMap<String, u32>,
+}
+
+impl Deck {
+    /// Compute the mana curve and composition of the deck, not counting sideboards.
+    /// # Errors
+    ///
+    /// Returns `DeckCodeError::UnknownCard` if a card is not in the database.
+    pub fn stats(&self, db: &CardDb) -> Result<DeckStats, DeckCodeError> {
+        let mut stats = DeckStats::default();
+        let mut total_cards = 0;
+        let mut total_cost = 0;
+
+        for (dbfid, count) in self.main_cards() {
+            let card = db.get(dbfid).ok_or(DeckCodeError::UnknownCard { dbfid })?;
+            let count = u32::from(count);
+            total_cards += count;
+            total_cost += card.cost * count;
+
+            let bucket = usize::try_from(card.cost)
+                .unwrap_or(usize::MAX)
+                .min(MANA_CURVE_BUCKETS - 1);
+            stats.mana_curve[bucket] += count;
+
+            match card.card_type {
+                CardType::Minion => stats.minions += count,
+                CardType::Spell => stats.spells += count,
+                CardType::Weapon => stats.weapons += count,
+                CardType::Location => stats.locations += count,
+                CardType::Hero => stats.heroes += count,
+                CardType::HeroPower | CardType::Enchantment | CardType::Unknown => {}
+            }
+
+            if card.card_class == "NEUTRAL" {
+                stats.neutral_cards += count;
+            } else {
+                stats.class_cards += count;
+            }
+
+            if let Some(rarity) = card.rarity {
+                *stats.rarities.entry(rarity).or_insert(0) += count;
+            }
+            for mechanic in &card.mechanics {
+                *stats.mechanics.entry(mechanic.clone()).or_insert(0) += count;
+            }
+        }
+
+        if total_cards > 0 {
+            stats.average_cost = f64::from(total_cost) / f64::from(total_cards);
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    const CARDS: &str = r#"[
+        {"cardClass":"MAGE","collectible":true,"cost":4,"dbfId":315,"id":"CS2_029","name":"Fireball","rarity":"FREE","set":"CORE","type":"SPELL"},
+        {"cardClass":"MAGE","collectible":true,"cost":3,"dbfId":192,"id":"EX1_295","mechanics":["SECRET"],"name":"Ice Block","rarity":"EPIC","set":"EXPERT1","type":"SPELL"},
+        {"cardClass":"NEUTRAL","collectible":true,"cost":8,"dbfId":1,"id":"EX1_298","name":"Ragnaros the Firelord","rarity":"LEGENDARY","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"NEUTRAL","collectible":true,"cost":2,"dbfId":2,"id":"EX1_012","mechanics":["SPELLPOWER","DEATHRATTLE"],"name":"Bloodmage Thalnos","rarity":"LEGENDARY","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"WARRIOR","collectible":true,"cost":0,"dbfId":3,"id":"EX1_411","name":"Gorehowl","rarity":"EPIC","set":"EXPERT1","type":"WEAPON"}
+    ]"#;
+
+    #[test]
+    fn stats_summarise_deck() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let deck = Deck::from_cards(
+            Format::Wild,
+            vec![637],
+            &[(2, 315), (2, 192), (1, 1), (1, 2), (2, 3)],
+            vec![(315, 1, 1)],
+        );
+        let stats = deck.stats(&db).expect("Known cards");
+        assert_eq!(stats.mana_curve, [2, 0, 1, 2, 2, 0, 0, 1]);
+        assert!((stats.average_cost - 3.0).abs() < f64::EPSILON);
+        assert_eq!((stats.minions, stats.spells, stats.weapons), (2, 4, 2));
+        assert_eq!((stats.class_cards, stats.neutral_cards), (6, 2));
+        assert_eq!(
+            stats.rarities,
+            [(Rarity::Free, 2), (Rarity::Epic, 4), (Rarity::Legendary, 2)]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(
+            stats.mechanics,
+            [("DEATHRATTLE", 1), ("SECRET", 2), ("SPELLPOWER", 1)]
+                .into_iter()
+                .map(|(mechanic, count)| (mechanic.to_owned(), count))
+                .collect()
+        );
+    }
+
+    #[test]
+    fn stats_of_empty_deck_are_zero() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let deck = Deck::from_cards(Format::Wild, vec![637], &[], Vec::new());
+        assert_eq!(deck.stats(&db), Ok(DeckStats::default()));
+    }
+
+    #[test]
+    fn stats_returns_err_for_unknown_card() {
+        let db = CardDb::from_json(CARDS).expect("Parsed correctly");
+        let deck = Deck::from_cards(Format::Wild, vec![637], &[(1, 99)], Vec::new());
+        assert_eq!(
+            deck.stats(&db),
+            Err(DeckCodeError::UnknownCard { dbfid: 99 })
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::fmt;
+
+use crate::cards::{CardDb, Rarity};
+use crate::deck::Deck;
+use crate::format::Format;
+use crate::rotation::{legal_sets, Date, LegalSets, LATEST_ROTATION};
+
+/// The deck building rules a deck is checked against.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ruleset {
+    /// The number of cards the deck must have, not counting sideboards.
+    pub deck_size: usize,
+    /// The maximum number of copies of a card.
+    pub max_copies: u8,
+    /// The maximum number of copies of a legendary card.
+    pub max_legendary_copies: u8,
+    /// The day the deck is played on, which decides the sets legal in the deck's format.
+    pub date: Date,
+    /// The sets cards in the deck may come from, e.g. `EXPERT1`, overriding the sets legal in the deck's format.
+    pub legal_sets: Option<Vec<String>>,
+}
+
+impl Default for Ruleset {
+    /// The rules of constructed play on `rotation::LATEST_ROTATION`, allowing the sets legal in the deck's format.
+    fn default() -> Self {
+        Self {
+            deck_size: 30,
+            max_copies: 2,
+            max_legendary_copies: 1,
+            date: LATEST_ROTATION,
+            legal_sets: None,
+        }
+    }
+}
+
+impl Ruleset {
+    /// The rules of constructed play on `date`, allowing the sets that were legal in the deck's format then.
+    #[must_use]
+    pub fn for_date(date: Date) -> Self {
+        Self {
+            date,
+            ..Self::default()
+        }
+    }
+}
+
+/// A way in which a deck breaks a `Ruleset`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The deck does not have `Ruleset::deck_size` cards.
+    WrongCardCount { expected: usize, found: usize },
+    /// The deck has more copies of a card than the ruleset allows for its rarity.
+    TooManyCopies { dbfid: u32, count: u8, limit: u8 },
+    /// The card belongs to a class other than that of the deck's heroes.
+    OffClassCard { dbfid: u32, card_class: String },
+    /// The card cannot be put in a deck, e.g. a token.
+    UncollectibleCard { dbfid: u32 },
+    /// The card's set is not legal in the deck's format on `Ruleset::date`, or not one of `Ruleset::legal_sets`.
+    NotLegalInFormat { dbfid: u32, set: String },
+    /// The card or hero is not in the card database, so it could not be checked.
+    UnknownCard { dbfid: u32 },
+    /// The sets legal in the deck's format on the ruleset's date are not in `rotation::ROTATIONS`, so no card was
+    /// checked against them. Set `Ruleset::legal_sets` to check a Twist deck or a date after `rotation::KNOWN_THROUGH`.
+    UnknownCardPool { format: Format, date: Date },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongCardCount { expected, found } => {
+                write!(f, "The deck has {found} cards instead of {expected}.")
+            }
+            Self::TooManyCopies {
+                dbfid,
+                count,
+                limit,
+            } => write!(
+                f,
+                "Card {dbfid} has {count} copies, more than the limit of {limit}."
+            ),
+            Self::OffClassCard { dbfid, card_class } => write!(
+                f,
+                "Card {dbfid} belongs to {card_class}, not the class of the deck."
+            ),
+            Self::UncollectibleCard { dbfid } => write!(f, "Card {dbfid} is not collectible."),
+            Self::NotLegalInFormat { dbfid, set } => write!(
+                f,
+                "Card {dbfid} is from the {set} set, which is not legal in this format."
+            ),
+            Self::UnknownCard { dbfid } => write!(f, "Card {dbfid} could not be resolved."),
+            Self::UnknownCardPool { format, date } => write!(
+                f,
+                "The sets legal in {format} on {date} are not known, so card sets were not checked."
+            ),
+        }
+    }
+}
+
+impl Deck {
+    /// Check the deck against the deck building rules in `ruleset`, returning every rule it breaks.
+    /// An empty list means the deck is legal.
+    ///
+    /// Cards must come from the sets legal in the deck's format on `Ruleset::date`, unless `Ruleset::legal_sets`
+    /// lists them. Cards in sideboards are not checked, as their rules depend on the card that owns the sideboard.
+    #[must_use]
+    pub fn validate(&self, db: &CardDb, ruleset: &Ruleset) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let legal_sets: Option<Vec<&str>> = match &ruleset.legal_sets {
+            Some(sets) => Some(sets.iter().map(String::as_str).collect()),
+            None => match legal_sets(self.format, ruleset.date) {
+                LegalSets::All => None,
+                LegalSets::Only(sets) => Some(sets.to_vec()),
+                LegalSets::Unknown => {
+                    violations.push(Violation::UnknownCardPool {
+                        format: self.format,
+                        date: ruleset.date,
+                    });
+                    None
+                }
+            },
+        };
+
+        let total_cards = self.total_cards();
+        if total_cards != ruleset.deck_size {
+            violations.push(Violation::WrongCardCount {
+                expected: ruleset.deck_size,
+                found: total_cards,
+            });
+        }
+
+        let mut hero_classes = Vec::new();
+        for &hero in &self.heroes {
+            match db.class_of(hero) {
+                Some(class) => hero_classes.push(class.card_class()),
+                None => violations.push(Violation::UnknownCard { dbfid: hero }),
+            }
+        }
+
+        for (count, dbfid, owner) in self.cards() {
+            if owner.is_some() {
+                continue;
+            }
+            let Some(card) = db.get(dbfid) else {
+                violations.push(Violation::UnknownCard { dbfid });
+                continue;
+            };
+
+            let limit = if card.rarity == Some(Rarity::Legendary) {
+                ruleset.max_legendary_copies
+            } else {
+                ruleset.max_copies
+            };
+            if count > limit {
+                violations.push(Violation::TooManyCopies {
+                    dbfid,
+                    count,
+                    limit,
+                });
+            }
+
+            let is_class_card =
+                |class: &str| class == card.card_class || card.classes.iter().any(|c| c == class);
+            if !hero_classes.is_empty()
+                && card.card_class != "NEUTRAL"
+                && !hero_classes.iter().any(|class| is_class_card(class))
+            {
+                violations.push(Violation::OffClassCard {
+                    dbfid,
+                    card_class: card.card_class.clone(),
+                });
+            }
+
+            if !card.collectible {
+                violations.push(Violation::UncollectibleCard { dbfid });
+            }
+
+            if let Some(legal_sets) = &legal_sets {
+                if !legal_sets.contains(&card.set.as_str()) {
+                    violations.push(Violation::NotLegalInFormat {
+                        dbfid,
+                        set: card.set.clone(),
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARDS: &str = r#"[
+        {"cardClass":"MAGE","collectible":true,"dbfId":637,"id":"HERO_08","name":"Jaina Proudmoore","set":"CORE","type":"HERO"},
+        {"cardClass":"MAGE","collectible":true,"dbfId":99001,"id":"HERO_08x","name":"Hero Skin","set":"HERO_SKINS","type":"HERO"},
+        {"cardClass":"MAGE","collectible":true,"cost":4,"dbfId":315,"id":"CS2_029","name":"Fireball","rarity":"FREE","set":"CORE","type":"SPELL"},
+        {"cardClass":"MAGE","collectible":true,"cost":3,"dbfId":192,"id":"EX1_295","name":"Ice Block","rarity":"EPIC","set":"EXPERT1","type":"SPELL"},
+        {"cardClass":"NEUTRAL","collectible":true,"cost":7,"dbfId":1,"id":"EX1_298","name":"Ragnaros the Firelord","rarity":"LEGENDARY","set":"EXPERT1","type":"MINION"},
+        {"cardClass":"ROGUE","collectible":true,"cost":0,"dbfId":2,"id":"CS2_072","name":"Backstab","rarity":"FREE","set":"CORE","type":"SPELL"},
+        {"cardClass":"NEUTRAL","collectible":false,"cost":1,"dbfId":3,"id":"GAME_005","name":"The Coin","set":"CORE","type":"SPELL"},
+        {"cardClass":"ROGUE","classes":["MAGE","ROGUE"],"collectible":true,"cost":2,"dbfId":4,"id":"CFM_066","name":"Kabal Lackey","rarity":"COMMON","set":"GANGS","type":"MINION"}
+    ]"#;
+
+    fn db() -> CardDb {
+        CardDb::from_json(CARDS).expect("Parsed correctly")
+    }
+
+    #[test]
+    fn legal_deck_has_no_violations() {
+        let deck = Deck::from_cards(
+            Format::Wild,
+            vec![637],
+            &[(2, 315), (2, 192), (1, 1), (2, 4)],
+            Vec::new(),
+        );
+        let ruleset = Ruleset {
+            deck_size: 7,
+            ..Ruleset::default()
+        };
+        assert_eq!(deck.validate(&db(), &ruleset), Vec::new());
+    }
+
+    #[test]
+    fn validate_reports_every_violation() {
+        let deck = Deck::from_cards(
+            Format::Standard,
+            vec![637],
+            &[(3, 315), (2, 1), (1, 2), (1, 3), (1, 99)],
+            Vec::new(),
+        );
+        let ruleset = Ruleset {
+            legal_sets: Some(vec!["CORE".to_owned()]),
+            ..Ruleset::default()
+        };
+        assert_eq!(
+            deck.validate(&db(), &ruleset),
+            vec![
+                Violation::WrongCardCount {
+                    expected: 30,
+                    found: 8
+                },
+                Violation::OffClassCard {
+                    dbfid: 2,
+                    card_class: "ROGUE".to_owned()
+                },
+                Violation::UncollectibleCard { dbfid: 3 },
+                Violation::UnknownCard { dbfid: 99 },
+                Violation::TooManyCopies {
+                    dbfid: 1,
+                    count: 2,
+                    limit: 1
+                },
+                Violation::NotLegalInFormat {
+                    dbfid: 1,
+                    set: "EXPERT1".to_owned()
+                },
+                Violation::TooManyCopies {
+                    dbfid: 315,
+                    count: 3,
+                    limit: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn hero_skins_take_class_from_card_data() {
+        let deck = Deck::from_cards(Format::Wild, vec![99001], &[(2, 192), (1, 2)], Vec::new());
+        let ruleset = Ruleset {
+            deck_size: 3,
+            ..Ruleset::default()
+        };
+        assert_eq!(
+            deck.validate(&db(), &ruleset),
+            vec![Violation::OffClassCard {
+                dbfid: 2,
+                card_class: "ROGUE".to_owned()
+            }]
+        );
+    }
+
+    #[test]
+    fn sideboard_cards_are_not_checked() {
+        let deck = Deck::from_cards(Format::Wild, vec![637], &[(1, 1)], vec![(2, 3, 1)]);
+        let ruleset = Ruleset {
+            deck_size: 1,
+            ..Ruleset::default()
+        };
+        assert_eq!(deck.validate(&db(), &ruleset), Vec::new());
+    }
+
+    #[test]
+    fn ruleset_for_date_uses_rotation_tables() {
+        let deck = Deck::from_cards(Format::Standard, vec![637], &[(2, 192)], Vec::new());
+        let before_rotation = Ruleset::for_date(Date::new(2021, 3, 29));
+        assert_eq!(
+            deck.validate(&db(), &before_rotation),
+            vec![Violation::WrongCardCount {
+                expected: 30,
+                found: 2
+            }]
+        );
+
+        let after_rotation = Ruleset::for_date(Date::new(2021, 3, 30));
+        assert_eq!(
+            deck.validate(&db(), &after_rotation)[1],
+            Violation::NotLegalInFormat {
+                dbfid: 192,
+                set: "EXPERT1".to_owned()
+            }
+        );
+
+        let wild = Deck::from_cards(Format::Wild, vec![637], &[(2, 192)], Vec::new());
+        assert_eq!(deck.validate(&db(), &after_rotation).len(), 2);
+        assert_eq!(wild.validate(&db(), &after_rotation).len(), 1);
+    }
+
+    #[test]
+    fn twist_deck_needs_legal_sets() {
+        let deck = Deck::from_cards(Format::Twist, vec![637], &[(2, 192)], Vec::new());
+        let ruleset = Ruleset {
+            deck_size: 2,
+            ..Ruleset::default()
+        };
+        assert_eq!(
+            deck.validate(&db(), &ruleset),
+            vec![Violation::UnknownCardPool {
+                format: Format::Twist,
+                date: LATEST_ROTATION
+            }]
+        );
+
+        let ruleset = Ruleset {
+            legal_sets: Some(vec!["EXPERT1".to_owned()]),
+            ..ruleset
+        };
+        assert_eq!(deck.validate(&db(), &ruleset), Vec::new());
+    }
+
+    #[test]
+    fn dates_after_rotation_tables_are_not_guessed() {
+        let deck = Deck::from_cards(Format::Standard, vec![637], &[(2, 192)], Vec::new());
+        let date = Date::new(2027, 1, 1);
+        let ruleset = Ruleset {
+            deck_size: 2,
+            ..Ruleset::for_date(date)
+        };
+        assert_eq!(
+            deck.validate(&db(), &ruleset),
+            vec![Violation::UnknownCardPool {
+                format: Format::Standard,
+                date
+            }]
+        );
+    }
+
+    #[test]
+    fn default_ruleset_checks_deck_format() {
+        // Classic only allows the 2014 card pool, so no set in the test data is legal.
+        let deck = Deck::from_cards(Format::Classic, vec![637], &[(2, 192)], Vec::new());
+        assert_eq!(
+            deck.validate(&db(), &Ruleset::default())[1],
+            Violation::NotLegalInFormat {
+                dbfid: 192,
+                set: "EXPERT1".to_owned()
+            }
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! JavaScript bindings through wasm-bindgen, enabled by the `wasm` feature. Build them with
+//! `cargo rustc --lib --target wasm32-unknown-unknown --features wasm --crate-type cdylib` and run `wasm-bindgen` on
+//! the output.
+//!
+//! Decks cross the boundary as plain objects in the shape described in `serialization`, e.g.
+//! `{format: "Standard", heroes: [637], cards: [{dbfid: 192, count: 1}]}`. Errors are thrown as JavaScript `Error`s
+//! named `DeckCodeError`, with a `code` property holding the `ErrorCode` of the `error::DeckCodeError` variant.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::deck::Deck;
+use crate::error::DeckCodeError;
+use crate::serialization::DeckDefinition;
+
+#[wasm_bindgen(typescript_custom_section)]
+const DECK_TYPES: &str = r#"
+export interface DeckObject {
+    format: "Unknown" | "Wild" | "Standard" | "Classic" | "Twist";
+    heroes: number[];
+    cards: { dbfid: number; count: number }[];
+    sideboards?: { owner: number; cards: { dbfid: number; count: number }[] }[];
+}
+
+export interface DeckCodeErrorObject extends Error {
+    name: "DeckCodeError";
+    code: ErrorCode;
+}
+"#;
+
+/// Identifies the `DeckCodeError` variant behind a thrown error.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    InvalidBase64,
+    MissingHeaderByte,
+    TruncatedSection,
+    InvalidVarint,
+    CountOverflow,
+    InvalidSideboardMarker,
+    SectionTooLarge,
+    InputTooLong,
+    TrailingData,
+    UnknownCard,
+    MissingDeckCode,
+    UnknownDeckFormat,
+    UnknownVersion,
+    ZeroCardCount,
+    CardCountOverflow,
+    SideboardOwnerNotInDeck,
+    CardNotInDeck,
+    NotEnoughCopies,
+    InvalidPatch,
+    Unknown,
+    /// Kept for `DeckCodeError::InvalidDeckEncoding`, which is no longer returned.
+    InvalidDeckEncoding,
+}
+
+impl From<&DeckCodeError> for ErrorCode {
+    fn from(error: &DeckCodeError) -> Self {
+        match error {
+            DeckCodeError::InvalidBase64(_) => Self::InvalidBase64,
+            DeckCodeError::MissingHeaderByte { .. } => Self::MissingHeaderByte,
+            DeckCodeError::TruncatedSection { .. } => Self::TruncatedSection,
+            DeckCodeError::InvalidVarint { .. } => Self::InvalidVarint,
+            DeckCodeError::CountOverflow { .. } => Self::CountOverflow,
+            DeckCodeError::InvalidSideboardMarker { .. } => Self::InvalidSideboardMarker,
+            DeckCodeError::SectionTooLarge { .. } => Self::SectionTooLarge,
+            DeckCodeError::InputTooLong { .. } => Self::InputTooLong,
+            DeckCodeError::TrailingData { .. } => Self::TrailingData,
+            DeckCodeError::UnknownCard { .. } => Self::UnknownCard,
+            DeckCodeError::MissingDeckCode => Self::MissingDeckCode,
+            DeckCodeError::UnknownDeckFormat { .. } => Self::UnknownDeckFormat,
+            DeckCodeError::UnknownVersion { .. } => Self::UnknownVersion,
+            DeckCodeError::ZeroCardCount { .. } => Self::ZeroCardCount,
+            DeckCodeError::CardCountOverflow { .. } => Self::CardCountOverflow,
+            DeckCodeError::SideboardOwnerNotInDeck { .. } => Self::SideboardOwnerNotInDeck,
+            DeckCodeError::CardNotInDeck { .. } => Self::CardNotInDeck,
+            DeckCodeError::NotEnoughCopies { .. } => Self::NotEnoughCopies,
+            DeckCodeError::InvalidPatch { .. } => Self::InvalidPatch,
+            DeckCodeError::Unknown => Self::Unknown,
+            #[allow(deprecated)]
+            DeckCodeError::InvalidDeckEncoding { .. } => Self::InvalidDeckEncoding,
+        }
+    }
+}
+
+/// A card as returned by `Deck.cards`. `owner` is left out for cards outside of sideboards.
+#[derive(Serialize)]
+struct CardObject {
+    count: u8,
+    dbfid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner: Option<u32>,
+}
+
+/// A decoded deck, wrapping `deck::Deck`.
+#[wasm_bindgen(js_name = Deck)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsDeck(Deck);
+
+#[wasm_bindgen(js_class = Deck)]
+impl JsDeck {
+    /// Decode a deck code, as `decode_deck_code` does.
+    /// # Errors
+    ///
+    /// Throws a `DeckCodeError` if the deck code is invalid.
+    #[wasm_bindgen(js_name = fromCode)]
+    pub fn from_code(code: &str) -> Result<JsDeck, JsValue> {
+        crate::decode_deck_code(code)
+            .map(JsDeck)
+            .map_err(|error| to_js_error(&error))
+    }
+
+    /// Build a canonical deck from a plain deck object.
+    /// # Errors
+    ///
+    /// Throws a `TypeError` if the value is not a deck object and a `DeckCodeError` if the deck is invalid.
+    #[wasm_bindgen(js_name = fromObject)]
+    pub fn from_object(value: JsValue) -> Result<JsDeck, JsValue> {
+        deck_from_value(value).map(JsDeck)
+    }
+
+    #[wasm_bindgen(js_name = toCode)]
+    #[must_use]
+    pub fn to_code(&self) -> String {
+        crate::encode_deck_code(&self.0)
+    }
+
+    /// The deck as a plain deck object.
+    /// # Errors
+    ///
+    /// Throws if the object cannot be created.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "DeckObject")]
+    pub fn to_object(&self) -> Result<JsValue, JsValue> {
+        to_value(&DeckDefinition::from(&self.0))
+    }
+
+    /// The name of the deck's format, e.g. `"Standard"`.
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn format(&self) -> String {
+        self.0.format.to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn heroes(&self) -> Vec<u32> {
+        self.0.heroes.clone()
+    }
+
+    /// The class of the deck's first hero, or `undefined` if the hero is not in `constants::HEROES`, which does not
+    /// list every hero skin.
+    #[wasm_bindgen(getter, js_name = "class")]
+    #[must_use]
+    pub fn class(&self) -> Option<String> {
+        self.0.class().map(|class| class.to_string())
+    }
+
+    /// The number of cards in the deck, not counting sideboards.
+    #[wasm_bindgen(getter, js_name = totalCards)]
+    #[must_use]
+    pub fn total_cards(&self) -> u32 {
+        u32::try_from(self.0.total_cards()).unwrap_or(u32::MAX)
+    }
+
+    /// Every card in the deck as `{count, dbfid, owner}` objects, as `Deck::cards` returns them.
+    /// # Errors
+    ///
+    /// Throws if the objects cannot be created.
+    #[wasm_bindgen(
+        getter,
+        unchecked_return_type = "{ count: number; dbfid: number; owner?: number }[]"
+    )]
+    pub fn cards(&self) -> Result<JsValue, JsValue> {
+        let cards: Vec<CardObject> = self
+            .0
+            .cards()
+            .into_iter()
+            .map(|(count, dbfid, owner)| CardObject {
+                count,
+                dbfid,
+                owner,
+            })
+            .collect();
+        to_value(&cards)
+    }
+
+    #[wasm_bindgen(js_name = countOf)]
+    #[must_use]
+    pub fn count_of(&self, dbfid: u32) -> u8 {
+        self.0.count_of(dbfid)
+    }
+
+    #[wasm_bindgen(js_name = sideboardCountOf)]
+    #[must_use]
+    pub fn sideboard_count_of(&self, dbfid: u32, owner: u32) -> u8 {
+        self.0.sideboard_count_of(dbfid, owner)
+    }
+}
+
+/// Decode a deck code into a plain deck object.
+/// # Errors
+///
+/// Throws a `DeckCodeError` if the deck code is invalid.
+#[wasm_bindgen(js_name = decodeDeckCode, unchecked_return_type = "DeckObject")]
+pub fn decode_deck_code(code: &str) -> Result<JsValue, JsValue> {
+    let deck = crate::decode_deck_code(code).map_err(|error| to_js_error(&error))?;
+    to_value(&DeckDefinition::from(&deck))
+}
+
+/// Encode a plain deck object as a canonical deck code.
+/// # Errors
+///
+/// Throws a `TypeError` if the value is not a deck object and a `DeckCodeError` if the deck is invalid.
+#[wasm_bindgen(js_name = encodeDeckCode)]
+pub fn encode_deck_code(
+    #[wasm_bindgen(unchecked_param_type = "DeckObject")] deck: JsValue,
+) -> Result<String, JsValue> {
+    deck_from_value(deck).map(|deck| crate::encode_deck_code(&deck))
+}
+
+fn deck_from_value(value: JsValue) -> Result<Deck, JsValue> {
+    let definition: DeckDefinition = serde_wasm_bindgen::from_value(value)
+        .map_err(|error| js_sys::TypeError::new(&error.to_string()))?;
+    definition.build().map_err(|error| to_js_error(&error))
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(Into::into)
+}
+
+/// An `Error` named `DeckCodeError` with the variant's `ErrorCode` as its `code`.
+fn to_js_error(error: &DeckCodeError) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    js_error.set_name("DeckCodeError");
+    // Setting a property on a fresh `Error` object cannot fail.
+    let _ = js_sys::Reflect::set(
+        &js_error,
+        &JsValue::from_str("code"),
+        &JsValue::from(ErrorCode::from(error)),
+    );
+    js_error.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_code_matches_variant() {
+        assert_eq!(
+            ErrorCode::from(&DeckCodeError::UnknownVersion { version: 2 }),
+            ErrorCode::UnknownVersion
+        );
+        assert_eq!(ErrorCode::from(&DeckCodeError::Unknown), ErrorCode::Unknown);
+    }
+}
@@ -0,0 +1,156 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const CODE: &str = "AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIAAA==";
+
+fn deck_codes(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_deck-codes"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Started deck-codes");
+    child
+        .stdin
+        .take()
+        .expect("Piped stdin")
+        .write_all(stdin.as_bytes())
+        .expect("Wrote stdin");
+    child.wait_with_output().expect("Ran deck-codes")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).expect("UTF-8 output")
+}
+
+#[test]
+fn decode_prints_format_heroes_and_cards() {
+    let output = deck_codes(&["decode", CODE], "");
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(
+        text.starts_with("Format: Standard\nHeroes: 637 (Mage)\nCards: 30\n  2x 113\n  1x 192\n")
+    );
+}
+
+#[test]
+fn decode_counts_more_than_255_cards() {
+    // Two cards with 200 copies each
+    let output = deck_codes(&["decode", "AAEBAQcAAAIByAECyAEA"], "");
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Cards: 400\n  200x 1\n  200x 2\n"));
+}
+
+#[test]
+fn decode_reads_code_from_stdin_and_prints_json() {
+    let output = deck_codes(&["decode", "--json"], &format!("{CODE}\n"));
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).expect("JSON output");
+    assert_eq!(json["format"], "Standard");
+    assert_eq!(
+        json["cards"][0],
+        serde_json::json!({"dbfid": 113, "count": 2})
+    );
+}
+
+#[test]
+fn encode_reads_toml_definition() {
+    let definition = "format = \"Wild\"\nheroes = [7]\n\n[[cards]]\ndbfid = 1\ncount = 3\n";
+    let output = deck_codes(&["encode", "--input", "toml"], definition);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "AAEBAQcAAAEBAwA=\n");
+}
+
+#[test]
+fn errors_exit_with_code_for_variant() {
+    let output = deck_codes(&["decode", "not a code!"], "");
+    assert_eq!(output.status.code(), Some(10));
+
+    let definition = r#"{"format":"Wild","heroes":[7],"cards":[{"dbfid":1,"count":0}]}"#;
+    let output = deck_codes(&["encode", "--json"], definition);
+    assert_eq!(output.status.code(), Some(23));
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stderr).expect("JSON error output");
+    assert_eq!(
+        json["error"],
+        serde_json::json!({"ZeroCardCount": {"dbfid": 1}})
+    );
+
+    let output = deck_codes(&["encode"], "{");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn diff_lists_changes_and_exits_with_1() {
+    let mut deck = deck_codes::decode_deck_code(CODE).expect("Valid code");
+    deck.remove_card(1, 42790).expect("Card is in the deck");
+    deck.add_card(1, 40297).expect("Card is in the deck");
+    let other = deck_codes::encode_deck_code(&deck);
+    let output = deck_codes(&["diff", CODE, &other], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "+1 40297\n-1 42790\n");
+
+    let output = deck_codes(&["diff", CODE, CODE], "");
+    assert!(output.status.success());
+    assert!(stdout(&output).is_empty());
+}
+
+#[test]
+fn canonicalize_fixes_pasted_code() {
+    let output = deck_codes(&["canonicalize", "--json"], "AAEBAQcAAAQBAwID\nAwMEAw");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).expect("JSON output");
+    assert_eq!(json["code"], "AAEBAQcAAAQBAwIDAwMEAwA=");
+    assert_eq!(
+        json["normalizations"],
+        serde_json::json!(["removed_internal_whitespace", "fixed_padding"])
+    );
+}
+
+#[test]
+fn validate_reports_non_canonical_code() {
+    let output = deck_codes(&["validate", "AAEBAQcAAAQBAwIDAwMEAw=="], "");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Valid Wild deck code.\nThe code is not canonical. Use `deck-codes canonicalize` to fix it.\n"
+    );
+}
+
+#[test]
+fn validate_checks_format_without_date() {
+    let cards = std::env::temp_dir().join(format!("deck-codes-cards-{}.json", std::process::id()));
+    std::fs::write(
+        &cards,
+        r#"[
+            {"cardClass":"MAGE","collectible":true,"dbfId":637,"id":"HERO_08","name":"Jaina Proudmoore","set":"CORE","type":"HERO"},
+            {"cardClass":"MAGE","collectible":true,"cost":3,"dbfId":192,"id":"EX1_295","name":"Ice Block","rarity":"EPIC","set":"EXPERT1","type":"SPELL"}
+        ]"#,
+    )
+    .expect("Wrote card data");
+    // A Standard deck with two copies of a card that rotated out in 2021
+    let output = deck_codes(
+        &[
+            "validate",
+            "--cards",
+            cards.to_str().expect("UTF-8 path"),
+            "AAECAf0EAAHAAQAA",
+        ],
+        "",
+    );
+    std::fs::remove_file(&cards).expect("Removed card data");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output)
+        .contains("Card 192 is from the EXPERT1 set, which is not legal in this format."));
+}
+
+#[test]
+fn validate_date_requires_cards() {
+    let output = deck_codes(
+        &["validate", "--date", "2025-03-25", "AAECAf0EAAHAAQAA"],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--cards"));
+}
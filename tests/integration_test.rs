@@ -1,5 +1,7 @@
 extern crate deck_codes;
-use deck_codes::{decode_deck_code, encode_deck_code, format::Format};
+use deck_codes::{
+    builder::DeckBuilder, decode_deck_code, encode_deck_code, export::DeckExport, format::Format,
+};
 
 #[test]
 fn pre_sideboard_standard_deck_string_matches_deck() {
@@ -174,8 +176,16 @@ fn post_sideboard_wild_dk_reverse() {
     assert_eq!(deck.format, Format::Wild);
     assert_eq!(deck.heroes, vec![78065]); // Code for The Lich King
 
+    // The game lists card 81972 twice in the single copy section. Decoding merges the entries, so the deck encodes
+    // to the canonical code with both copies in the two copy section.
+    assert_eq!(deck.count_of(81972), 2);
+    assert_eq!(deck.total_cards(), 30);
     let reverse_code = encode_deck_code(&deck);
-    assert_eq!(code, reverse_code);
+    assert_eq!(
+        reverse_code,
+        "AAEBAfHhBAAP9eMEguQEseYEjvEEtIAFrqEF88gF6oAG6akG/7oG/8kGkMsGpdwGpPQGpvQGAAA="
+    );
+    assert_eq!(decode_deck_code(&reverse_code), Ok(deck));
 }
 
 #[test]
@@ -271,3 +281,78 @@ fn sideboard_standard_reverse() {
     let reverse_code = encode_deck_code(&deck);
     assert_eq!(code, reverse_code);
 }
+
+#[test]
+fn built_deck_encodes_to_standard_deck_string() {
+    let code = "AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIAAA==";
+    let deck = DeckBuilder::new(Format::Standard)
+        .hero(637) // Jaina
+        .cards([
+            (2, 41496), // Primordial Glyph
+            (2, 113),   // Counter Spell
+            (1, 42790), // Bonemare
+            (2, 195),   // Mirror Entity
+            (2, 315),   // Fireball
+            (2, 405),   // Mana Wyrm
+            (1, 192),   // Ice Block
+            (2, 555),   // Arcane Intellect
+            (2, 662),   // Frostbolt
+            (2, 748),   // Kirin Tor Mage
+            (2, 39715), // Firelands Portal
+            (1, 39841), // Medivh, the Guardian
+            (2, 39767), // Medivh’s Valet
+            (2, 40297), // Volcanic Potion
+            (2, 40583), // Kabal Crystal Runner
+            (1, 42718), // Ghastly Conjurer
+            (2, 41153), // Arcanologist
+        ])
+        .build()
+        .expect("Built correctly");
+    assert_eq!(deck.total_cards(), 30);
+    assert_eq!(encode_deck_code(&deck), code);
+}
+
+#[test]
+fn classic_export_block_matches_deck() {
+    let text = "### Rogue
+# Class: Rogue
+# Format: Classic
+#
+# 2x (0) Backstab
+# 2x (0) Preparation
+# 2x (0) Shadowstep
+# 1x (1) Cold Blood
+# 1x (1) Conceal
+# 2x (1) Deadly Poison
+# 1x (2) Blade Flurry
+# 1x (2) Bloodmage Thalnos
+# 2x (2) Eviscerate
+# 2x (2) Sap
+# 2x (2) Shiv
+# 2x (3) Earthen Ring Farseer
+# 1x (3) Edwin VanCleef
+# 1x (3) Fan of Knives
+# 2x (3) SI:7 Agent
+# 1x (4) Leeroy Jenkins
+# 1x (5) Assassin's Blade
+# 2x (5) Azure Drake
+# 2x (5) Gadgetzan Auctioneer
+#
+AAEDAaIHCIKWBNyWBPigBIahBLWhBNyhBN+hBKWjBAv8lQT9lQTqlgT7lgT6oATUoQTdoQTkoQTnoQTooQSTogQA
+#
+# To use this deck, copy it to your clipboard and create a new deck in Hearthstone
+";
+    let export = DeckExport::parse(text).expect("Parsed correctly");
+    assert_eq!(export.name.as_deref(), Some("Rogue"));
+    assert_eq!(export.format_label.as_deref(), Some("Classic"));
+    assert_eq!(export.deck.format, Format::Classic);
+    assert_eq!(export.deck.heroes, vec![930]); // Code for Valeera
+    assert_eq!(export.listed_cards.len(), 19);
+
+    let listed_total: usize = export
+        .listed_cards
+        .iter()
+        .map(|card| usize::from(card.count))
+        .sum();
+    assert_eq!(listed_total, export.deck.total_cards());
+}
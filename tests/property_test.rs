@@ -0,0 +1,152 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+extern crate deck_codes;
+use deck_codes::{
+    builder::DeckBuilder, deck::Deck, decode_deck_code, encode_deck_code, format::Format,
+};
+
+use base64::prelude::*;
+use proptest::prelude::*;
+
+/// Counts that are either small enough to be plausible or large enough to overflow the section arithmetic.
+fn hostile_count() -> impl Strategy<Value = u32> {
+    prop_oneof![
+        0..8u32,
+        Just(u32::MAX),
+        Just(u32::MAX - 1),
+        Just(u32::MAX / 2),
+        any::<u32>(),
+    ]
+}
+
+/// A valid header followed by section lengths that may lie about how much data follows them.
+fn hostile_deck_bytes() -> impl Strategy<Value = Vec<u32>> {
+    (
+        0..=4u32,
+        prop::collection::vec(hostile_count(), 0..8),
+        prop::collection::vec(any::<u32>(), 0..16),
+    )
+        .prop_map(|(format, counts, tail)| {
+            let mut bytes = vec![0, 1, format];
+            for (count, value) in counts.into_iter().zip(tail.iter().cycle()) {
+                bytes.push(count);
+                bytes.push(*value);
+            }
+            bytes.extend(tail);
+            bytes
+        })
+}
+
+/// Appends a section length followed by its entries.
+fn push_section<T, F: Fn(&T) -> Vec<u32>>(bytes: &mut Vec<u32>, entries: &[T], entry: F) {
+    bytes.push(u32::try_from(entries.len()).expect("Small section"));
+    for value in entries {
+        bytes.extend(entry(value));
+    }
+}
+
+/// A well-formed deck drawn from a few dbfids, so that cards repeat within and across sections, with sideboard
+/// entries that repeat the same card and owner and copy counts that add up to more than 255.
+fn repetitive_deck_bytes() -> impl Strategy<Value = Vec<u32>> {
+    let dbfid = || 1..4u32;
+    // The Nx sections may hold any count, including counts that belong in the 1x and 2x sections.
+    let copies = || prop_oneof![1..8u32, 200..=255u32];
+    (
+        prop::collection::vec(dbfid(), 0..4),
+        prop::collection::vec(dbfid(), 0..4),
+        prop::collection::vec((dbfid(), copies()), 0..4),
+        prop::collection::vec((dbfid(), dbfid()), 0..4),
+        prop::collection::vec((dbfid(), dbfid()), 0..4),
+        prop::collection::vec((dbfid(), copies(), dbfid()), 0..4),
+    )
+        .prop_map(
+            |(singles, doubles, multis, sideboard_singles, sideboard_doubles, sideboard_multis)| {
+                let mut bytes = vec![0, 1, 1, 1, 7];
+                push_section(&mut bytes, &singles, |&dbfid| vec![dbfid]);
+                push_section(&mut bytes, &doubles, |&dbfid| vec![dbfid]);
+                push_section(&mut bytes, &multis, |&(dbfid, count)| vec![dbfid, count]);
+                bytes.push(1);
+                push_section(&mut bytes, &sideboard_singles, |&(dbfid, owner)| {
+                    vec![dbfid, owner]
+                });
+                push_section(&mut bytes, &sideboard_doubles, |&(dbfid, owner)| {
+                    vec![dbfid, owner]
+                });
+                push_section(&mut bytes, &sideboard_multis, |&(dbfid, count, owner)| {
+                    vec![dbfid, count, owner]
+                });
+                bytes
+            },
+        )
+}
+
+/// Inputs found to break the round trip or panic, kept as fixed regression cases.
+#[test]
+fn known_hostile_codes_survive_a_round_trip() {
+    for code in [
+        // The same sideboard card and owner in the 2x and Nx sections
+        "AAEBAQcBCQAAAQABBQkBBQEJ",
+        // Two cards with 200 copies each
+        "AAEBAQcAAAIByAECyAEA",
+        // Card 5 in the 1x and 2x sections, card 6 twice in the 1x section and card 7 in the Nx section
+        "AAEBAQcDBQYGAQUBBwIA",
+    ] {
+        let deck = decode_deck_code(code).expect("Decoded correctly");
+        let _ = deck.total_cards();
+        assert_eq!(decode_deck_code(&encode_deck_code(&deck)), Ok(deck));
+    }
+}
+
+proptest! {
+    #[test]
+    fn decode_deck_code_never_panics_on_any_string(code in any::<String>()) {
+        let _ = decode_deck_code(&code);
+    }
+
+    #[test]
+    fn decode_deck_code_never_panics_on_any_bytes(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+        let _ = decode_deck_code(&BASE64_STANDARD.encode(bytes));
+    }
+
+    #[test]
+    fn new_never_panics_on_hostile_lengths(bytes in hostile_deck_bytes()) {
+        let _ = Deck::new(&bytes);
+    }
+
+    #[test]
+    fn decoded_decks_survive_a_round_trip(bytes in hostile_deck_bytes()) {
+        if let Ok(deck) = Deck::new(&bytes) {
+            prop_assert_eq!(Deck::new(&deck.to_byte_array()), Ok(deck));
+        }
+    }
+
+    #[test]
+    fn repetitive_decks_survive_a_round_trip(bytes in repetitive_deck_bytes()) {
+        if let Ok(deck) = Deck::new(&bytes) {
+            let main_copies: usize = deck
+                .cards()
+                .iter()
+                .filter(|card| card.2.is_none())
+                .map(|card| usize::from(card.0))
+                .sum();
+            prop_assert_eq!(deck.total_cards(), main_copies);
+            for (count, dbfid, owner) in deck.cards() {
+                if owner.is_none() {
+                    prop_assert_eq!(deck.count_of(dbfid), count);
+                }
+            }
+            prop_assert_eq!(decode_deck_code(&encode_deck_code(&deck)), Ok(deck));
+        }
+    }
+
+    #[test]
+    fn built_decks_survive_a_round_trip(
+        heroes in prop::collection::vec(any::<u32>(), 0..3),
+        cards in prop::collection::btree_map(any::<u32>(), 1..=5u8, 0..30),
+    ) {
+        let deck = DeckBuilder::new(Format::Wild)
+            .cards(cards.into_iter().map(|(dbfid, count)| (count, dbfid)));
+        let deck = heroes.into_iter().fold(deck, DeckBuilder::hero).build().expect("Valid deck");
+        prop_assert_eq!(decode_deck_code(&encode_deck_code(&deck)), Ok(deck));
+    }
+}
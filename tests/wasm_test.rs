@@ -0,0 +1,62 @@
+//! Run with `wasm-pack test --node -- --features wasm`.
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use deck_codes::wasm::{decode_deck_code, encode_deck_code, ErrorCode, JsDeck};
+use js_sys::Reflect;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const CODE: &str = "AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIAAA==";
+const PRE_SIDEBOARD_CODE: &str =
+    "AAECAf0EBMABobcC3s0Cps4CDXHDAbsClQOrBJYF7AWjtgLXtgLpugKHvQLBwQKYxAIA";
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+    Reflect::get(value, &JsValue::from_str(key)).expect("Property is readable")
+}
+
+#[wasm_bindgen_test]
+fn decode_returns_plain_object() {
+    let deck = decode_deck_code(CODE).expect("Valid code");
+    assert_eq!(get(&deck, "format"), "Standard");
+    let first_card = get(&get(&deck, "cards"), "0");
+    assert_eq!(get(&first_card, "dbfid"), 113);
+    assert_eq!(get(&first_card, "count"), 2);
+}
+
+#[wasm_bindgen_test]
+fn encode_round_trips_plain_object() {
+    let deck = decode_deck_code(PRE_SIDEBOARD_CODE).expect("Valid code");
+    assert_eq!(encode_deck_code(deck).expect("Valid deck"), CODE);
+}
+
+#[wasm_bindgen_test]
+fn deck_accessors_match_decoded_deck() {
+    let deck = JsDeck::from_code(CODE).expect("Valid code");
+    assert_eq!(deck.format(), "Standard");
+    assert_eq!(deck.heroes(), vec![637]);
+    assert_eq!(deck.class(), Some("Mage".to_owned()));
+    assert_eq!(deck.total_cards(), 30);
+    assert_eq!(deck.count_of(315), 2);
+    assert_eq!(deck.sideboard_count_of(315, 1), 0);
+    assert_eq!(deck.to_code(), CODE);
+    let from_object =
+        JsDeck::from_object(deck.to_object().expect("Created object")).expect("Valid deck object");
+    assert_eq!(from_object, deck);
+}
+
+#[wasm_bindgen_test]
+fn errors_carry_error_code() {
+    let error = decode_deck_code("not a code!").expect_err("Invalid base64");
+    assert_eq!(get(&error, "name"), "DeckCodeError");
+    assert_eq!(get(&error, "code"), JsValue::from(ErrorCode::InvalidBase64));
+
+    let deck = decode_deck_code(CODE).expect("Valid code");
+    Reflect::set(
+        &deck,
+        &JsValue::from_str("format"),
+        &JsValue::from_str("Modern"),
+    )
+    .expect("Property is writable");
+    let error = encode_deck_code(deck).expect_err("Unknown format");
+    assert!(error.is_instance_of::<js_sys::TypeError>());
+}